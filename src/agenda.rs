@@ -0,0 +1,133 @@
+use crate::components::todo_list::Todo;
+use crate::due;
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime};
+
+/// A header in the agenda with the todos under it, in the order they are due.
+pub struct Section<'a> {
+    pub title: String,
+    pub overdue: bool,
+    pub todos: Vec<&'a Todo>,
+}
+
+/// The unfinished todos due within `days` days counting today, grouped by day.
+/// Overdue todos come first, under a section of their own.
+pub fn build(todos: &[Todo], now: NaiveDateTime, days: u32) -> Vec<Section<'_>> {
+    let today = now.date();
+    let end = today
+        .checked_add_signed(Duration::days(i64::from(days)))
+        .unwrap_or(NaiveDate::MAX);
+    let mut due: Vec<(&Todo, NaiveDateTime, bool)> = todos
+        .iter()
+        // recurring todos are never done for good
+        .filter(|t| !t.metadata.finished || t.metadata.is_recurring())
+        .filter_map(|t| t.metadata.due_date.map(|d| (t, d)))
+        .filter(|(_, d)| d.date() < end)
+        .map(|(t, d)| {
+            // todos due on a day without a time are only overdue once the day is over
+            let overdue = if all_day(d) {
+                d.date() < today
+            } else {
+                d < now
+            };
+            (t, d, overdue)
+        })
+        .collect();
+    due.sort_by_key(|(_, d, overdue)| (!overdue, *d));
+
+    let mut sections: Vec<Section> = Vec::new();
+    for (t, d, overdue) in due {
+        let title = if overdue {
+            "Overdue".to_string()
+        } else {
+            day_title(d.date(), today)
+        };
+        match sections.last_mut() {
+            Some(s) if s.title == title => s.todos.push(t),
+            _ => sections.push(Section {
+                title,
+                overdue,
+                todos: vec![t],
+            }),
+        }
+    }
+    sections
+}
+
+/// Names a day like "Today", "Tomorrow" or "Mon 20 Oct".
+pub fn day_title(date: NaiveDate, today: NaiveDate) -> String {
+    match (date - today).num_days() {
+        0 => "Today".to_string(),
+        1 => "Tomorrow".to_string(),
+        _ if date.year() == today.year() => date.format("%a %-d %b").to_string(),
+        _ => date.format("%a %-d %b %Y").to_string(),
+    }
+}
+
+/// Whether the todo is due on a day rather than at a time, which is stored as the end of
+/// the day, or as midnight by older versions and imports.
+fn all_day(due: NaiveDateTime) -> bool {
+    due.time() == NaiveTime::MIN || due.time() == due::end_of_day()
+}
+
+/// When a todo in the agenda is due. The day is in the header unless it is overdue.
+pub fn due_label(due: NaiveDateTime, overdue: bool) -> String {
+    let time = if all_day(due) {
+        String::new()
+    } else {
+        due.format("%-I:%M %P").to_string()
+    };
+    if overdue {
+        format!("{} {time}", due.format("%-d %b"))
+            .trim_end()
+            .to_string()
+    } else {
+        time
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn todo(name: &str, due: &str) -> Todo {
+        let mut t = Todo {
+            name: name.to_string(),
+            ..Default::default()
+        };
+        t.metadata.due_date = Some(NaiveDateTime::parse_from_str(due, "%Y-%m-%d %H:%M").unwrap());
+        t
+    }
+
+    #[test]
+    fn groups_by_day() {
+        let now = NaiveDateTime::parse_from_str("2026-10-18 10:00", "%Y-%m-%d %H:%M").unwrap();
+        let mut done = todo("done", "2026-10-18 12:00");
+        done.metadata.finished = true;
+        let todos = [
+            todo("later", "2026-10-20 09:00"),
+            todo("too far", "2026-10-25 00:00"),
+            todo("tonight", "2026-10-18 20:00"),
+            todo("late", "2026-10-01 09:00"),
+            todo("this morning", "2026-10-18 08:00"),
+            todo("tomorrow", "2026-10-19 00:00"),
+            todo("all day", "2026-10-18 00:00"),
+            todo("end of day", "2026-10-18 23:59"),
+            done,
+        ];
+        let sections = build(&todos, now, 7);
+        let titles: Vec<_> = sections.iter().map(|s| s.title.as_str()).collect();
+        assert_eq!(titles, ["Overdue", "Today", "Tomorrow", "Tue 20 Oct"]);
+        let names: Vec<_> = sections[0].todos.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, ["late", "this morning"]);
+        let names: Vec<_> = sections[1].todos.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, ["all day", "tonight", "end of day"]);
+        assert_eq!(
+            due_label(sections[1].todos[0].metadata.due_date.unwrap(), false),
+            ""
+        );
+        assert_eq!(
+            due_label(sections[1].todos[2].metadata.due_date.unwrap(), false),
+            ""
+        );
+    }
+}
@@ -1,26 +1,44 @@
+use super::components::AgendaComponent;
+use super::components::CalendarViewComponent;
+use super::components::DiskChangedComponent;
+use super::components::ListSwitcherComponent;
 use super::components::SkimmerComponent;
+use super::components::SubtaskInputComponent;
+use super::components::SyncConflictComponent;
+use super::components::TagFilterComponent;
 use super::components::TodoListComponent;
 use super::components::{notification::FlashMsg, NotificationComponent};
 use crate::components::due_date::DueDateComponent;
+use crate::components::list_switcher::{ListsAction, SwitcherPurpose};
 use crate::components::skimmer::SkimmerAction;
-use crate::components::todo_list::ListAction;
+use crate::components::todo_list::{DiskChoice, ListAction, TodoId};
 use crate::components::TodoInputComponent;
 use crate::keys::keymap::SharedKeyList;
 use crate::keys::ToodKeyList;
+use crate::settings::ToodSettings;
+use crate::sync::{Conflict, Resolution};
+use crate::tags;
 use crate::theme::theme::SharedTheme;
 use crate::theme::ToodTheme;
 use crate::widgets::hint_bar::BarType;
 use anyhow::Result;
-use chrono::NaiveDateTime;
+use chrono::{NaiveDate, NaiveDateTime};
 use kanal::unbounded;
 use kanal::Receiver;
 
 pub struct App {
     pub todo_list: TodoListComponent,
     pub todo_input: TodoInputComponent,
+    pub subtask_input: SubtaskInputComponent,
     pub skimmer: SkimmerComponent,
+    pub list_switcher: ListSwitcherComponent,
+    pub tag_filter: TagFilterComponent,
+    pub sync_conflict: SyncConflictComponent,
+    pub disk_changed: DiskChangedComponent,
     pub notification: NotificationComponent,
     pub due_date: DueDateComponent,
+    pub calendar_view: CalendarViewComponent,
+    pub agenda: AgendaComponent,
     pub keys: SharedKeyList,
     pub theme: SharedTheme,
     pub state: AppState,
@@ -32,7 +50,14 @@ pub enum AppMessage {
     InputState(AppState),
     Skimmer(SkimmerAction),
     UpdateList(ListAction),
+    Lists(ListsAction),
+    SetTagFilter(Vec<String>),
     SetDueDate(NaiveDateTime),
+    JumpToTodo(TodoId),
+    Reschedule(TodoId, NaiveDate),
+    SyncConflicts(Vec<Conflict>),
+    ResolveConflict(Resolution),
+    ResolveDiskConflict(DiskChoice),
     ReInitTerminal,
     #[default]
     NoAction,
@@ -47,19 +72,41 @@ pub enum AppState {
     DueDate,
     Find,
     Move,
+    Lists,
+    MoveToList,
+    AddSubtask,
+    TagFilter,
+    SyncConflict,
+    DiskChanged,
+    Calendar,
+    Agenda,
 }
 
 impl App {
-    pub fn new() -> App {
+    pub fn new(list_name: String) -> App {
         let (sender, receiver) = unbounded::<FlashMsg>();
         let keys = ToodKeyList::init(sender.clone());
         let theme = ToodTheme::init(sender.clone());
+        let settings = ToodSettings::init(sender.clone());
         App {
-            todo_list: TodoListComponent::load(keys.clone(), theme.clone(), sender.clone()),
-            todo_input: TodoInputComponent::new(keys.clone(), theme.clone()),
+            todo_list: TodoListComponent::load(
+                list_name,
+                keys.clone(),
+                theme.clone(),
+                settings.clone(),
+                sender.clone(),
+            ),
+            todo_input: TodoInputComponent::new(keys.clone(), theme.clone(), sender.clone()),
+            subtask_input: SubtaskInputComponent::new(keys.clone(), theme.clone()),
             skimmer: SkimmerComponent::new(keys.clone(), theme.clone()),
+            list_switcher: ListSwitcherComponent::new(keys.clone(), theme.clone(), sender.clone()),
+            tag_filter: TagFilterComponent::new(keys.clone(), theme.clone()),
+            sync_conflict: SyncConflictComponent::new(keys.clone(), theme.clone()),
+            disk_changed: DiskChangedComponent::new(keys.clone(), theme.clone()),
             notification: NotificationComponent::new(theme.clone()),
-            due_date: DueDateComponent::new(keys.clone(), theme.clone(), sender),
+            due_date: DueDateComponent::new(keys.clone(), theme.clone()),
+            calendar_view: CalendarViewComponent::new(keys.clone(), theme.clone()),
+            agenda: AgendaComponent::new(keys.clone(), theme.clone(), settings),
             keys,
             theme,
             state: AppState::Normal,
@@ -67,6 +114,15 @@ impl App {
         }
     }
 
+    pub fn tick(&mut self) -> Result<()> {
+        self.todo_list.tick();
+        // wait for the user to be back in the list before asking about changes on disk
+        if self.state == AppState::Normal && self.todo_list.disk_conflict().is_some() {
+            self.update_state(AppState::DiskChanged)?;
+        }
+        Ok(())
+    }
+
     pub fn poll_flash_messages(&mut self) {
         if let Ok(Some(msg)) = self.flash_rx.try_recv() {
             self.notification.flash(msg);
@@ -82,12 +138,16 @@ impl App {
             }
             AppState::EditTodo => {
                 self.todo_list.load_hintbar(BarType::Edit);
-                if let Some((t, i)) = self.todo_list.selected() {
-                    self.todo_input.populate_with(t, i);
+                if let Some(t) = self.todo_list.selected() {
+                    self.todo_input.populate_with(t);
                 }
             }
             AppState::AddTodo => {
                 self.todo_list.load_hintbar(BarType::Edit);
+                // coming back from the calendar must not reset the typed tags
+                if self.state == AppState::Normal {
+                    self.todo_input.prefill_tags(self.todo_list.tag_filter());
+                }
             }
             AppState::Normal => {
                 self.todo_list.load_hintbar(BarType::Normal);
@@ -95,14 +155,48 @@ impl App {
             AppState::Move => {
                 self.todo_list.load_hintbar(BarType::Move);
             }
+            AppState::TagFilter => {
+                self.todo_list.load_hintbar(BarType::Filter);
+                self.tag_filter
+                    .open(self.todo_list.all_tags(), self.todo_list.tag_filter());
+            }
+            AppState::AddSubtask => {
+                self.todo_list.load_hintbar(BarType::Subtask);
+            }
+            AppState::SyncConflict => {
+                self.todo_list.load_hintbar(BarType::Conflict);
+            }
+            AppState::DiskChanged => {
+                self.todo_list.load_hintbar(BarType::DiskChanged);
+                if let Some(conflict) = self.todo_list.disk_conflict() {
+                    self.disk_changed.open(&conflict.names);
+                }
+            }
+            AppState::Lists => {
+                self.todo_list.load_hintbar(BarType::Lists);
+                self.list_switcher
+                    .open(&self.todo_list.list_name, SwitcherPurpose::Switch)?;
+            }
+            AppState::MoveToList => {
+                self.todo_list.load_hintbar(BarType::Lists);
+                self.list_switcher
+                    .open(&self.todo_list.list_name, SwitcherPurpose::MoveTodo)?;
+            }
             AppState::DueDate => {
                 self.todo_list.load_hintbar(BarType::DueDate);
-                if self.state == AppState::EditTodo {
-                    if let Some(dt) = self.todo_input.get_due_date() {
-                        self.due_date.set_date_time(dt)?;
-                    }
+                // also when adding, the due date may have been typed already
+                if let Some(dt) = self.todo_input.get_due_date() {
+                    self.due_date.set_date_time(dt);
                 }
             }
+            AppState::Calendar => {
+                self.todo_list.load_hintbar(BarType::Calendar);
+                self.calendar_view.open(self.todo_list.todos_ref());
+            }
+            AppState::Agenda => {
+                self.todo_list.load_hintbar(BarType::Agenda);
+                self.agenda.open(self.todo_list.todos_ref());
+            }
         }
         self.state = state;
         Ok(())
@@ -131,21 +225,95 @@ impl App {
                 self.todo_list.add_todo(t)?;
                 "Added todo"
             }
-            ListAction::Replace(t, i) => {
-                self.todo_list.replace(t, i)?;
+            ListAction::Replace(t) => {
+                self.todo_list.replace(t)?;
                 "Edited todo"
             }
+            ListAction::AddSubtask(name) => {
+                self.todo_list.add_subtask(name)?;
+                "Added subtask"
+            }
         };
 
         self.notification.flash(FlashMsg::info(msg));
         self.todo_list.load_hintbar(BarType::Normal);
         self.todo_input.clear();
+        self.subtask_input.clear();
+        self.state = AppState::Normal;
+        Ok(())
+    }
+
+    pub fn lists_action(&mut self, lists_action: ListsAction) -> Result<()> {
+        match lists_action {
+            ListsAction::Switch(name) => {
+                if self.todo_list.switch_list(name.clone())? {
+                    self.notification
+                        .flash(FlashMsg::info(format!("Switched to list {name}")));
+                }
+            }
+            ListsAction::MoveTodo(name) => self.todo_list.move_selected_to(&name)?,
+            ListsAction::Renamed { from, to } => {
+                if self.todo_list.list_name == from {
+                    self.todo_list.list_name = to.clone();
+                }
+                self.notification
+                    .flash(FlashMsg::info(format!("Renamed list {from} to {to}")));
+                // stay in the switcher after renaming
+                return Ok(());
+            }
+        }
+        self.todo_list.load_hintbar(BarType::Normal);
         self.state = AppState::Normal;
         Ok(())
     }
 
+    pub fn set_tag_filter(&mut self, filter: Vec<String>) {
+        let msg = if filter.is_empty() {
+            "Cleared tag filter".to_string()
+        } else {
+            format!("Showing {}", tags::format(&filter))
+        };
+        self.todo_list.set_tag_filter(filter);
+        self.notification.flash(FlashMsg::info(msg));
+        self.todo_list.load_hintbar(BarType::Normal);
+        self.state = AppState::Normal;
+    }
+
     pub fn set_due_date(&mut self, d: NaiveDateTime) {
         self.todo_input.set_due_date(d);
         self.state = AppState::AddTodo;
     }
+
+    pub fn jump_to_todo(&mut self, id: TodoId) -> Result<()> {
+        self.todo_list.select(id);
+        self.update_state(AppState::Normal)
+    }
+
+    pub fn reschedule(&mut self, id: TodoId, date: NaiveDate) -> Result<()> {
+        self.todo_list.reschedule(id, date)?;
+        self.calendar_view.refresh(self.todo_list.todos_ref());
+        Ok(())
+    }
+
+    pub fn show_conflicts(&mut self, conflicts: Vec<Conflict>) -> Result<()> {
+        self.sync_conflict.open(conflicts);
+        self.update_state(AppState::SyncConflict)
+    }
+
+    pub fn resolve_conflict(&mut self, resolution: Resolution) -> Result<()> {
+        if let Some(conflict) = self.sync_conflict.current() {
+            self.todo_list.resolve_conflict(conflict, resolution)?;
+        }
+        if !self.sync_conflict.advance() {
+            self.notification
+                .flash(FlashMsg::info("Resolved all conflicts"));
+            self.update_state(AppState::Normal)?;
+        }
+        Ok(())
+    }
+
+    pub fn resolve_disk_conflict(&mut self, choice: DiskChoice) -> Result<()> {
+        self.todo_list.resolve_disk_conflict(choice);
+        self.update_state(AppState::Normal)
+    }
 }
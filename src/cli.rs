@@ -0,0 +1,824 @@
+use crate::agenda;
+use crate::components::todo_list::{Todo, TodoId, TodoMetadata, TIME_FORMAT};
+use crate::due;
+use crate::filter::Filter;
+use crate::formats::Format;
+use crate::git::{self, Repo};
+use crate::priority::Priority;
+use crate::quick_add;
+use crate::recurrence::Recurrence;
+use crate::remind::{self, LeadTime};
+use crate::settings::ToodSettings;
+use crate::store::{self, ListLock, TodoListSerde, DEFAULT_LIST};
+use crate::subtask;
+use crate::sync::{self, Resolution};
+use crate::tags;
+use crate::view::{GroupMode, ListView, SortMode};
+use anyhow::{anyhow, bail, Result};
+use chrono::{Local, NaiveDate, NaiveDateTime};
+use std::fs;
+use std::io::{self, Read};
+use std::path::PathBuf;
+use std::time::Duration;
+
+static USAGE: &str = "\
+usage: tood [--list <name>] [--file <path>] [command]
+
+options:
+  -l, --list <name>                    use the named list instead of the default one
+  -f, --file <path>                    keep the default list in this file, also set with
+                                       `TOOD_FILE`, otherwise a `.tood.ron` in the current
+                                       directory or at the root of its git repository is used
+
+commands:
+  (none)                               open the interactive todo list
+  add <name> [--desc <d>] [--due <date>] [--repeat <rule>] [--tags <tags>]
+      [--priority <level>] [--remind <lead>]
+                                       add a new todo, the name can also carry
+                                       `#tags`, a `!priority` and a `due:<date>`
+  list [--tag <tag>]... [--sort <order>] [--group <grouping>]
+                                       print all todos with their ids, optionally
+                                       only those carrying every given tag
+  agenda [--days <n>]                  print the unfinished todos due in the next days by
+                                       day, overdue ones first, `agenda_days` in the
+                                       settings sets how far ahead to look
+  done <id>                            toggle the finished state of a todo
+  rm <id>                              remove a todo
+  edit <id> [--name <n>] [--desc <d>] [--due <date>] [--repeat <rule>] [--tags <tags>]
+       [--priority <level>] [--remind <lead>]
+                                       change fields of an existing todo
+  mv <id> <list>                       move a todo to another list
+  export --format <format> [--output <file>] [--filter <terms>]...
+                                       write the todos of the list to a file or stdout,
+                                       optionally only those matching every filter
+  import <file> [--format <format>]    add the todos from a file to the list, the format
+                                       is guessed from the extension if not given,
+                                       `-` reads from stdin
+  log [--count <n>]                    show the latest changes to the list, needs
+                                       `git_history` turned on in the settings
+  restore <commit> <id>                bring a todo back to how it was in a commit
+  sync [--keep <side>]                 sync the list with its CalDAV collection, todos
+                                       changed on both sides are only listed unless
+                                       `--keep local` or `--keep remote` is given
+  lists                                print all lists
+  lists new <name>                     create a new list
+  lists rename <name> <new name>       rename a list
+  lists rm <name>                      delete a list, its file is kept with a
+                                       `.deleted` extension
+  remind                               send reminders for todos in all lists that are
+                                       due soon or overdue, each reminder is sent once
+  daemon [--interval <seconds>]        keep sending reminders, checking every minute
+  help                                 print this message
+
+dates are given as `YYYY-MM-DD` or `YYYY-MM-DD HH:MM`
+repeat rules are `daily`, `weekly mon,fri`, `monthly 15`, `every 3 days`
+or `never` to stop an existing todo from recurring
+tags are separated by spaces or commas, e.g. `work,urgent`
+priority levels are `none`, `low`, `medium`, `high` and `urgent`
+sort orders are `manual`, `priority`, `due`, `added`, `edited`, `name` and `unfinished`
+groupings are `none`, `due` and `status`
+formats are `todotxt`, `ical`, `json` and `markdown`, which can only be exported
+filter terms are `#<tag>`, `done`, `open`, `overdue`, `today` and `priority:<level>`
+reminder lead times are given as `30m`, `2h` or `1d`, or `default` to use the one from the settings";
+
+pub struct Cli {
+    pub list: String,
+    pub file: Option<PathBuf>,
+    pub command: Command,
+}
+
+impl Cli {
+    pub fn parse<I: Iterator<Item = String>>(args: I) -> Result<Self> {
+        let mut args = args.peekable();
+        let mut list = DEFAULT_LIST.to_string();
+        let mut file = None;
+        while let Some(flag) =
+            args.next_if(|a| matches!(a.as_str(), "--list" | "-l" | "--file" | "-f"))
+        {
+            let value = flag_value(&mut args, &flag)?;
+            if flag == "--list" || flag == "-l" {
+                store::validate_list_name(&value)?;
+                list = value;
+            } else {
+                file = Some(PathBuf::from(value));
+            }
+        }
+        Ok(Self {
+            list,
+            file,
+            command: Command::parse(args)?,
+        })
+    }
+}
+
+pub enum ListsCommand {
+    Show,
+    New(String),
+    Rename(String, String),
+    Delete(String),
+}
+
+pub enum Command {
+    Tui,
+    Add {
+        name: String,
+        description: String,
+        due_date: Option<NaiveDateTime>,
+        recurrence: Option<Recurrence>,
+        tags: Vec<String>,
+        priority: Priority,
+        remind_before: Option<LeadTime>,
+    },
+    List {
+        tags: Vec<String>,
+        sort: SortMode,
+        group: GroupMode,
+    },
+    Agenda(Option<u32>),
+    Done(TodoId),
+    Remove(TodoId),
+    Edit {
+        id: TodoId,
+        name: Option<String>,
+        description: Option<String>,
+        due_date: Option<NaiveDateTime>,
+        recurrence: Option<Option<Recurrence>>,
+        tags: Option<Vec<String>>,
+        priority: Option<Priority>,
+        remind_before: Option<Option<LeadTime>>,
+    },
+    Move(TodoId, String),
+    Export {
+        format: Format,
+        output: Option<PathBuf>,
+        filter: Filter,
+    },
+    Import {
+        path: PathBuf,
+        format: Option<Format>,
+    },
+    Log(usize),
+    Restore(String, TodoId),
+    Sync(Option<Resolution>),
+    Lists(ListsCommand),
+    Remind,
+    Daemon(Duration),
+    Help,
+}
+
+impl Command {
+    pub fn parse<I: Iterator<Item = String>>(mut args: I) -> Result<Self> {
+        let cmd = match args.next() {
+            Some(cmd) => cmd,
+            None => return Ok(Self::Tui),
+        };
+
+        match cmd.as_str() {
+            "add" => {
+                let mut words = Vec::new();
+                let mut description = String::new();
+                let mut due_date = None;
+                let mut recurrence = None;
+                let mut tags = Vec::new();
+                let mut priority = None;
+                let mut remind_before = None;
+                while let Some(arg) = args.next() {
+                    match arg.as_str() {
+                        "--remind" => {
+                            remind_before = parse_lead(&flag_value(&mut args, "--remind")?)?
+                        }
+                        "--desc" => description = flag_value(&mut args, "--desc")?,
+                        "--priority" => {
+                            priority = Some(flag_value(&mut args, "--priority")?.parse()?)
+                        }
+                        "--tags" => tags = tags::parse(&flag_value(&mut args, "--tags")?),
+                        "--repeat" => {
+                            recurrence = parse_recurrence(&flag_value(&mut args, "--repeat")?)?
+                        }
+                        "--due" => due_date = Some(parse_date(&flag_value(&mut args, "--due")?)?),
+                        _ if arg.starts_with("--") => bail!("unexpected argument '{arg}'"),
+                        _ => words.push(arg),
+                    }
+                }
+                // flags win over what is written in the name
+                let quick = quick_add::parse(&words.join(" "), Local::now().naive_local());
+                if quick.name.is_empty() {
+                    bail!("missing todo name");
+                }
+                for tag in quick.tags {
+                    if !tags.contains(&tag) {
+                        tags.push(tag);
+                    }
+                }
+                Ok(Self::Add {
+                    name: quick.name,
+                    description,
+                    due_date: due_date.or(quick.due),
+                    recurrence,
+                    tags,
+                    priority: priority.or(quick.priority).unwrap_or_default(),
+                    remind_before,
+                })
+            }
+            "list" | "ls" => {
+                let mut tags = Vec::new();
+                let mut sort = SortMode::Manual;
+                let mut group = GroupMode::None;
+                while let Some(arg) = args.next() {
+                    match arg.as_str() {
+                        "--group" => group = flag_value(&mut args, "--group")?.parse()?,
+                        "--sort" => sort = flag_value(&mut args, "--sort")?.parse()?,
+                        "--tag" => tags.extend(tags::parse(&flag_value(&mut args, "--tag")?)),
+                        _ => bail!("unexpected argument '{arg}'"),
+                    }
+                }
+                Ok(Self::List { tags, sort, group })
+            }
+            "agenda" => {
+                let mut days = None;
+                while let Some(arg) = args.next() {
+                    match arg.as_str() {
+                        "--days" | "-d" => {
+                            let n = flag_value(&mut args, &arg)?;
+                            days = Some(n.parse().map_err(|_| anyhow!("invalid days '{n}'"))?);
+                        }
+                        _ => bail!("unexpected argument '{arg}'"),
+                    }
+                }
+                Ok(Self::Agenda(days))
+            }
+            "done" => Ok(Self::Done(parse_id(args.next())?)),
+            "rm" => Ok(Self::Remove(parse_id(args.next())?)),
+            "edit" => {
+                let id = parse_id(args.next())?;
+                let (mut name, mut description, mut due_date) = (None, None, None);
+                let (mut recurrence, mut tags, mut priority) = (None, None, None);
+                let mut remind_before = None;
+                while let Some(arg) = args.next() {
+                    match arg.as_str() {
+                        "--remind" => {
+                            remind_before = Some(parse_lead(&flag_value(&mut args, "--remind")?)?)
+                        }
+                        "--priority" => {
+                            priority = Some(flag_value(&mut args, "--priority")?.parse()?)
+                        }
+                        "--tags" => tags = Some(tags::parse(&flag_value(&mut args, "--tags")?)),
+                        "--repeat" => {
+                            recurrence =
+                                Some(parse_recurrence(&flag_value(&mut args, "--repeat")?)?)
+                        }
+                        "--name" => name = Some(flag_value(&mut args, "--name")?),
+                        "--desc" => description = Some(flag_value(&mut args, "--desc")?),
+                        "--due" => due_date = Some(parse_date(&flag_value(&mut args, "--due")?)?),
+                        _ => bail!("unexpected argument '{arg}'"),
+                    }
+                }
+                Ok(Self::Edit {
+                    id,
+                    name,
+                    description,
+                    due_date,
+                    recurrence,
+                    tags,
+                    priority,
+                    remind_before,
+                })
+            }
+            "mv" => {
+                let id = parse_id(args.next())?;
+                let list = args.next().ok_or_else(|| anyhow!("missing list name"))?;
+                Ok(Self::Move(id, list))
+            }
+            "export" => {
+                let (mut format, mut output) = (None, None);
+                let mut filter = Filter::default();
+                while let Some(arg) = args.next() {
+                    match arg.as_str() {
+                        "--filter" => filter.extend(flag_value(&mut args, "--filter")?.parse()?),
+                        "--format" => format = Some(flag_value(&mut args, "--format")?.parse()?),
+                        "--output" | "-o" => output = Some(flag_value(&mut args, &arg)?.into()),
+                        _ => bail!("unexpected argument '{arg}'"),
+                    }
+                }
+                let format = format.ok_or_else(|| anyhow!("missing '--format'"))?;
+                Ok(Self::Export {
+                    format,
+                    output,
+                    filter,
+                })
+            }
+            "import" => {
+                let (mut path, mut format) = (None, None);
+                while let Some(arg) = args.next() {
+                    match arg.as_str() {
+                        "--format" => format = Some(flag_value(&mut args, "--format")?.parse()?),
+                        _ if path.is_none() => path = Some(PathBuf::from(arg)),
+                        _ => bail!("unexpected argument '{arg}'"),
+                    }
+                }
+                let path = path.ok_or_else(|| anyhow!("missing file to import"))?;
+                Ok(Self::Import { path, format })
+            }
+            "lists" => {
+                let cmd = match args.next().as_deref() {
+                    None => ListsCommand::Show,
+                    Some("new") => ListsCommand::New(list_arg(&mut args)?),
+                    Some("rename") => {
+                        ListsCommand::Rename(list_arg(&mut args)?, list_arg(&mut args)?)
+                    }
+                    Some("rm") => ListsCommand::Delete(list_arg(&mut args)?),
+                    Some(arg) => bail!("unknown lists command '{arg}'"),
+                };
+                Ok(Self::Lists(cmd))
+            }
+            "log" => {
+                let mut count = 20;
+                while let Some(arg) = args.next() {
+                    match arg.as_str() {
+                        "--count" | "-n" => {
+                            let n = flag_value(&mut args, &arg)?;
+                            count = n.parse().map_err(|_| anyhow!("invalid count '{n}'"))?;
+                        }
+                        _ => bail!("unexpected argument '{arg}'"),
+                    }
+                }
+                Ok(Self::Log(count))
+            }
+            "restore" => {
+                let rev = args
+                    .next()
+                    .ok_or_else(|| anyhow!("missing commit to restore from"))?;
+                let id = parse_id(args.next())?;
+                Ok(Self::Restore(rev, id))
+            }
+            "sync" => {
+                let mut keep = None;
+                while let Some(arg) = args.next() {
+                    match arg.as_str() {
+                        "--keep" => {
+                            let side = flag_value(&mut args, "--keep")?;
+                            keep = Some(match side.as_str() {
+                                "local" => Resolution::KeepLocal,
+                                "remote" => Resolution::KeepRemote,
+                                _ => bail!("invalid side '{side}', use `local` or `remote`"),
+                            });
+                        }
+                        _ => bail!("unexpected argument '{arg}'"),
+                    }
+                }
+                Ok(Self::Sync(keep))
+            }
+            "remind" => Ok(Self::Remind),
+            "daemon" => {
+                let mut interval = Duration::from_secs(60);
+                while let Some(arg) = args.next() {
+                    match arg.as_str() {
+                        "--interval" => {
+                            let secs = flag_value(&mut args, "--interval")?;
+                            let secs: u64 = secs
+                                .parse()
+                                .map_err(|_| anyhow!("invalid interval '{secs}'"))?;
+                            interval = Duration::from_secs(secs.max(1));
+                        }
+                        _ => bail!("unexpected argument '{arg}'"),
+                    }
+                }
+                Ok(Self::Daemon(interval))
+            }
+            "help" | "-h" | "--help" => Ok(Self::Help),
+            _ => bail!("unknown command '{cmd}'\n\n{USAGE}"),
+        }
+    }
+}
+
+fn flag_value<I: Iterator<Item = String>>(args: &mut I, flag: &str) -> Result<String> {
+    args.next()
+        .ok_or_else(|| anyhow!("missing value for '{flag}'"))
+}
+
+fn list_arg<I: Iterator<Item = String>>(args: &mut I) -> Result<String> {
+    args.next().ok_or_else(|| anyhow!("missing list name"))
+}
+
+fn parse_id(arg: Option<String>) -> Result<TodoId> {
+    let arg = arg.ok_or_else(|| anyhow!("missing todo id"))?;
+    arg.parse().map_err(|_| anyhow!("invalid todo id '{arg}'"))
+}
+
+fn parse_date(s: &str) -> Result<NaiveDateTime> {
+    if let Ok(dt) = NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M") {
+        return Ok(dt);
+    }
+    match NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+        Ok(d) => Ok(NaiveDateTime::new(d, due::end_of_day())),
+        Err(_) => bail!("invalid date '{s}', expected `YYYY-MM-DD [HH:MM]`"),
+    }
+}
+
+fn parse_recurrence(s: &str) -> Result<Option<Recurrence>> {
+    match s {
+        "never" | "none" => Ok(None),
+        _ => Ok(Some(s.parse()?)),
+    }
+}
+
+fn parse_lead(s: &str) -> Result<Option<LeadTime>> {
+    match s {
+        "default" => Ok(None),
+        _ => Ok(Some(s.parse()?)),
+    }
+}
+
+fn todo_mut(todo_data: &mut TodoListSerde, id: TodoId) -> Result<&mut Todo> {
+    todo_data
+        .get_mut(id)
+        .ok_or_else(|| anyhow!("no todo with id {id}"))
+}
+
+fn run_lists(cmd: ListsCommand) -> Result<()> {
+    match cmd {
+        ListsCommand::Show => {
+            for name in store::list_names()? {
+                println!("{name}");
+            }
+        }
+        ListsCommand::New(name) => {
+            store::create_list(&name)?;
+            println!("Created list {name}");
+        }
+        ListsCommand::Rename(from, to) => {
+            ensure_list(&from)?;
+            store::rename_list(&from, &to)?;
+            println!("Renamed list {from} to {to}");
+        }
+        ListsCommand::Delete(name) => {
+            ensure_list(&name)?;
+            let deleted = store::delete_list(&name)?;
+            println!(
+                "Deleted list {name}, its todos are kept in {}",
+                deleted.display()
+            );
+        }
+    }
+    Ok(())
+}
+
+fn ensure_list(name: &str) -> Result<()> {
+    if !store::list_exists(name)? {
+        bail!("no list named '{name}', create it with `tood lists new {name}`");
+    }
+    Ok(())
+}
+
+/// Commits a change to the lists, warning when `list` is left out of the history.
+fn record(settings: &ToodSettings, list: &str, change: &str) -> Result<()> {
+    git::record(settings, change)?;
+    if let Some(reason) = git::untracked(settings, list)? {
+        eprintln!("tood: {reason}");
+    }
+    Ok(())
+}
+
+fn open_repo(settings: &ToodSettings) -> Result<Repo> {
+    Repo::open(settings)?.ok_or_else(|| {
+        anyhow!("git history is off, turn it on with `git_history: Some(true)` in settings.ron")
+    })
+}
+
+pub fn run(list: &str, cmd: Command) -> Result<()> {
+    match cmd {
+        Command::Tui => unreachable!("the interactive ui is not a cli command"),
+        Command::Help => {
+            println!("{USAGE}");
+            return Ok(());
+        }
+        Command::Lists(cmd) => return run_lists(cmd),
+        Command::Remind => {
+            let settings = ToodSettings::load()?;
+            if remind::check(&settings)? == 0 {
+                println!("Nothing to remind of");
+            }
+            return Ok(());
+        }
+        Command::Daemon(interval) => {
+            let settings = ToodSettings::load()?;
+            return remind::daemon(&settings, interval);
+        }
+        _ => ensure_list(list)?,
+    }
+
+    // held until the changes are stored, so other instances don't overwrite them
+    let _lock = ListLock::acquire(list)?;
+    let (mut todo_data, recovered) = TodoListSerde::load_or_recover(list)?;
+    if let Some(msg) = recovered {
+        eprintln!("tood: {msg}");
+    }
+    let settings = ToodSettings::load()?;
+
+    match cmd {
+        Command::Tui | Command::Help | Command::Lists(_) | Command::Remind | Command::Daemon(_) => {
+            unreachable!()
+        }
+        Command::List {
+            tags: filter,
+            sort,
+            group,
+        } => {
+            let mut order: Vec<usize> = (0..todo_data.todos.len())
+                .filter(|i| tags::matches(&todo_data.todos[*i].tags, &filter))
+                .collect();
+            ListView { sort, group }.arrange(&todo_data.todos, &mut order);
+
+            let now = Local::now().naive_local();
+            let mut section = None;
+            for t in order.into_iter().map(|i| &todo_data.todos[i]) {
+                if group != GroupMode::None {
+                    let title = group.title(group.group_of(t, now));
+                    if section != Some(title) {
+                        println!("{title}");
+                        section = Some(title);
+                    }
+                }
+                let finished = if t.metadata.is_recurring() {
+                    "[∞]"
+                } else if t.metadata.finished {
+                    "[x]"
+                } else {
+                    "[ ]"
+                };
+                let due = if let Some(dd) = t.metadata.due_date {
+                    format!(" (due {})", dd.format(TIME_FORMAT))
+                } else {
+                    String::new()
+                };
+                let progress = if t.subtasks.is_empty() {
+                    String::new()
+                } else {
+                    let (done, total) = subtask::progress(&t.subtasks);
+                    format!(" [{done}/{total}]")
+                };
+                let tags = if t.tags.is_empty() {
+                    String::new()
+                } else {
+                    format!(" {}", tags::format(&t.tags))
+                };
+                let priority = match t.metadata.priority {
+                    Priority::None => String::new(),
+                    p => format!(" ({p})"),
+                };
+                println!(
+                    "{:>3} {finished} {}{priority}{progress}{tags}{due}",
+                    t.id, t.name
+                );
+
+                let mut paths = Vec::new();
+                subtask::flatten(&t.subtasks, &[], &mut paths);
+                for path in paths {
+                    let sub = subtask::get(&t.subtasks, &path).unwrap();
+                    let finished = if sub.finished { "[x]" } else { "[ ]" };
+                    let indent = "  ".repeat(path.len());
+                    println!("    {indent}{finished} {}", sub.name);
+                }
+            }
+        }
+        Command::Agenda(days) => {
+            let days = days.unwrap_or(settings.agenda_days);
+            let sections = agenda::build(&todo_data.todos, Local::now().naive_local(), days);
+            if sections.is_empty() {
+                println!("Nothing due");
+            }
+            for section in sections {
+                println!("{}", section.title);
+                for t in section.todos {
+                    let due = t.metadata.due_date.unwrap_or_default();
+                    let when = agenda::due_label(due, section.overdue);
+                    let priority = match t.metadata.priority {
+                        Priority::None => String::new(),
+                        p => format!(" ({p})"),
+                    };
+                    let tags = if t.tags.is_empty() {
+                        String::new()
+                    } else {
+                        format!(" {}", tags::format(&t.tags))
+                    };
+                    println!("{:>3} {when:>14} {}{priority}{tags}", t.id, t.name);
+                }
+            }
+        }
+        Command::Add {
+            name,
+            description,
+            due_date,
+            recurrence,
+            tags,
+            priority,
+            remind_before,
+        } => {
+            let change = format!("Add: {name}");
+            let id = todo_data.push(Todo {
+                name,
+                description,
+                tags,
+                metadata: TodoMetadata {
+                    due_date,
+                    priority,
+                    recurrence,
+                    remind_before,
+                    ..Default::default()
+                },
+                ..Default::default()
+            });
+            todo_data.store(list)?;
+            record(&settings, list, &change)?;
+            println!("Added todo {id}");
+        }
+        Command::Done(id) => {
+            let todo = todo_mut(&mut todo_data, id)?;
+            if let Some(next) = todo.complete_occurrence() {
+                let change = format!("Complete: {}", todo.name);
+                todo_data.store(list)?;
+                record(&settings, list, &change)?;
+                println!("Next occurrence: {}", next.format(TIME_FORMAT));
+                return Ok(());
+            }
+            todo.toggle_finished();
+            let (msg, change) = if todo.metadata.finished {
+                ("Marked todo as finished", "Complete")
+            } else {
+                ("Marked todo as unfinished", "Reopen")
+            };
+            let change = format!("{change}: {}", todo.name);
+            todo_data.store(list)?;
+            record(&settings, list, &change)?;
+            println!("{msg}");
+        }
+        Command::Remove(id) => {
+            let todo = todo_data
+                .remove(id)
+                .ok_or_else(|| anyhow!("no todo with id {id}"))?;
+            todo_data.store(list)?;
+            record(&settings, list, &format!("Remove: {}", todo.name))?;
+            println!("Removed todo: {}", todo.name);
+        }
+        Command::Edit {
+            id,
+            name,
+            description,
+            due_date,
+            recurrence,
+            tags,
+            priority,
+            remind_before,
+        } => {
+            let todo = todo_mut(&mut todo_data, id)?;
+            if let Some(name) = name {
+                todo.name = name;
+            }
+            if let Some(description) = description {
+                todo.description = description;
+            }
+            if due_date.is_some() {
+                todo.metadata.due_date = due_date;
+            }
+            if let Some(recurrence) = recurrence {
+                todo.metadata.recurrence = recurrence;
+            }
+            if let Some(tags) = tags {
+                todo.tags = tags;
+            }
+            if let Some(priority) = priority {
+                todo.metadata.priority = priority;
+            }
+            if let Some(remind_before) = remind_before {
+                todo.metadata.remind_before = remind_before;
+            }
+            todo.metadata.edited_at = Some(Local::now());
+            let change = format!("Edit: {}", todo.name);
+            todo_data.store(list)?;
+            record(&settings, list, &change)?;
+            println!("Edited todo {id}");
+        }
+        Command::Export {
+            format,
+            output,
+            filter,
+        } => {
+            let now = Local::now().naive_local();
+            let todos: Vec<Todo> = todo_data
+                .todos
+                .into_iter()
+                .filter(|t| filter.matches(t, now))
+                .collect();
+            let out = format.export(list, &todos);
+            match output {
+                Some(path) => {
+                    fs::write(&path, out)?;
+                    println!("Exported {} todos to {}", todos.len(), path.display());
+                }
+                None => print!("{out}"),
+            }
+        }
+        Command::Import { path, format } => {
+            let format = format.or_else(|| Format::detect(&path)).ok_or_else(|| {
+                anyhow!(
+                    "can't tell the format of {}, pass '--format'",
+                    path.display()
+                )
+            })?;
+            let input = if path.as_os_str() == "-" {
+                let mut input = String::new();
+                io::stdin().read_to_string(&mut input)?;
+                input
+            } else {
+                fs::read_to_string(&path)?
+            };
+            let todos = format.import(&input)?;
+            let count = todos.len();
+            for t in todos {
+                todo_data.push(t);
+            }
+            todo_data.store(list)?;
+            record(&settings, list, &format!("Import {count} todos"))?;
+            println!("Imported {count} todos into {list}");
+        }
+        Command::Move(id, target) => {
+            ensure_list(&target)?;
+            if target == list {
+                bail!("todo {id} is already in list {list}");
+            }
+            let todo = todo_data
+                .remove(id)
+                .ok_or_else(|| anyhow!("no todo with id {id}"))?;
+            let name = todo.name.clone();
+
+            // removed here first, so a failed write can't leave the todo in both lists
+            todo_data.store(list)?;
+            let moved = ListLock::acquire(&target).and_then(|_lock| {
+                let mut target_data = TodoListSerde::load(&target)?;
+                let new_id = target_data.push(todo.clone());
+                target_data.store(&target)?;
+                Ok(new_id)
+            });
+            let new_id = match moved {
+                Ok(new_id) => new_id,
+                Err(e) => {
+                    todo_data.restore(todo);
+                    todo_data.store(list)?;
+                    return Err(e);
+                }
+            };
+            record(&settings, list, &format!("Move to {target}: {name}"))?;
+            println!("Moved '{name}' to {target} as todo {new_id}");
+        }
+        Command::Log(count) => {
+            let repo = open_repo(&settings)?;
+            let commits = repo.log(list, count)?;
+            if commits.is_empty() {
+                println!("No changes recorded for {list} yet");
+            }
+            for c in commits {
+                println!("{} {} {}", c.hash, c.date, c.message);
+            }
+        }
+        Command::Restore(rev, id) => {
+            let repo = open_repo(&settings)?;
+            let todo = repo
+                .list_at(&rev, list)?
+                .remove(id)
+                .ok_or_else(|| anyhow!("no todo with id {id} in commit {rev}"))?;
+            let name = todo.name.clone();
+            todo_data.restore(todo);
+            todo_data.store(list)?;
+            record(&settings, list, &format!("Restore: {name}"))?;
+            println!("Restored '{name}' from {rev}");
+        }
+        Command::Sync(keep) => {
+            let report = sync::sync(list, &mut todo_data)?;
+            todo_data.store(list)?;
+            record(&settings, list, &report.describe())?;
+            println!("{}", report.describe());
+            for conflict in report.conflicts.iter() {
+                match keep {
+                    Some(resolution) => {
+                        sync::resolve(list, &mut todo_data, conflict, resolution)?;
+                        todo_data.store(list)?;
+                        let change = format!("Resolve conflict: {}", conflict.local.name);
+                        record(&settings, list, &change)?;
+                    }
+                    None => println!(
+                        "conflict on todo {}: '{}' here, '{}' on the server",
+                        conflict.id, conflict.local.name, conflict.remote.name
+                    ),
+                }
+            }
+            if !report.conflicts.is_empty() {
+                match keep {
+                    Some(Resolution::KeepLocal) => println!("Kept the local versions"),
+                    Some(Resolution::KeepRemote) => println!("Kept the server versions"),
+                    None => println!("Run `tood sync --keep local` or `--keep remote` to resolve"),
+                }
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,189 @@
+use super::todo_list::{Todo, TodoId};
+use crate::agenda;
+use crate::app::{AppMessage, AppState};
+use crate::keys::keymap::SharedKeyList;
+use crate::settings::settings::SharedSettings;
+use crate::theme::theme::SharedTheme;
+use chrono::Local;
+use crossterm::event::KeyEvent;
+use ratatui::backend::Backend;
+use ratatui::layout::Rect;
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Span, Spans};
+use ratatui::widgets::{Block, Borders, Clear, List, ListItem, ListState};
+use ratatui::Frame;
+use std::error::Error;
+use tui_utils::component::Component;
+use tui_utils::keys::key_match;
+use tui_utils::state::{Boundary, BoundedState, StateWrap};
+use tui_utils::LIST_HIGHLIGHT_SYMBOL;
+
+/// The todos due in the next days, grouped by day.
+pub struct AgendaComponent {
+    /// Rows at the bottom of the screen kept free for the hint bar.
+    pub hints_height: u16,
+    rows: Vec<Row>,
+    // the todo rows, which are the ones that can be selected
+    entries: Vec<usize>,
+    state: BoundedState,
+    display_state: ListState,
+    keys: SharedKeyList,
+    theme: SharedTheme,
+    settings: SharedSettings,
+}
+
+enum Row {
+    Header {
+        title: String,
+        overdue: bool,
+    },
+    Todo {
+        when: String,
+        overdue: bool,
+        todo: Todo,
+    },
+}
+
+impl AgendaComponent {
+    pub fn new(keys: SharedKeyList, theme: SharedTheme, settings: SharedSettings) -> Self {
+        Self {
+            hints_height: 0,
+            rows: Vec::new(),
+            entries: Vec::new(),
+            state: BoundedState::default(),
+            display_state: ListState::default(),
+            keys,
+            theme,
+            settings,
+        }
+    }
+
+    pub fn open(&mut self, todos: &[Todo]) {
+        let now = Local::now().naive_local();
+        self.rows.clear();
+        self.entries.clear();
+        for section in agenda::build(todos, now, self.settings.agenda_days) {
+            self.rows.push(Row::Header {
+                title: section.title,
+                overdue: section.overdue,
+            });
+            for t in section.todos {
+                self.entries.push(self.rows.len());
+                self.rows.push(Row::Todo {
+                    when: agenda::due_label(
+                        t.metadata.due_date.unwrap_or_default(),
+                        section.overdue,
+                    ),
+                    overdue: section.overdue,
+                    todo: t.clone(),
+                });
+            }
+        }
+        self.state = BoundedState::new(Boundary::from(&self.entries), StateWrap::Enable);
+        if !self.entries.is_empty() {
+            self.state.first();
+        }
+    }
+
+    fn selected_id(&self) -> Option<TodoId> {
+        let i = self.state.inner().selected()?;
+        match self.rows.get(*self.entries.get(i)?) {
+            Some(Row::Todo { todo, .. }) => Some(todo.id),
+            _ => None,
+        }
+    }
+
+    fn todo_item(&self, t: &Todo, when: &str, overdue: bool) -> ListItem<'static> {
+        let when_style = if overdue {
+            Style::default().fg(self.theme.due_overdue)
+        } else {
+            Style::default().fg(self.theme.border)
+        };
+        let mut spans = vec![
+            Span::styled(format!("  {when:>14} "), when_style),
+            Span::styled(t.name.clone(), Style::default().fg(self.theme.todo_title)),
+        ];
+        for tag in t.tags.iter() {
+            spans.push(Span::raw(" "));
+            spans.push(Span::styled(
+                format!(" {tag} "),
+                Style::default()
+                    .fg(self.theme.tag_fg)
+                    .bg(self.theme.tag_color(tag)),
+            ));
+        }
+        ListItem::new(Spans::from(spans))
+    }
+}
+
+impl Component for AgendaComponent {
+    type Message = AppMessage;
+
+    fn draw<B: Backend>(&mut self, f: &mut Frame<B>, _dim: bool) {
+        let size = f.size();
+        let rect = Rect {
+            height: size.height.saturating_sub(self.hints_height),
+            ..size
+        };
+
+        let items: Vec<ListItem> = if self.rows.is_empty() {
+            vec![ListItem::new("Nothing due")]
+        } else {
+            self.rows
+                .iter()
+                .map(|row| match row {
+                    Row::Header { title, overdue } => {
+                        let color = if *overdue {
+                            self.theme.due_overdue
+                        } else {
+                            self.theme.section_title
+                        };
+                        ListItem::new(Spans::from(Span::styled(
+                            title.clone(),
+                            Style::default().fg(color).add_modifier(Modifier::BOLD),
+                        )))
+                    }
+                    Row::Todo {
+                        when,
+                        overdue,
+                        todo,
+                    } => self.todo_item(todo, when, *overdue),
+                })
+                .collect()
+        };
+        let title = match self.settings.agenda_days {
+            1 => "Agenda - today".to_string(),
+            n => format!("Agenda - next {n} days"),
+        };
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(self.theme.move_mode_border))
+                    .title(title),
+            )
+            .highlight_style(Style::default().bg(self.theme.selected_bg))
+            .highlight_symbol(LIST_HIGHLIGHT_SYMBOL);
+
+        let selected = self.state.inner().selected();
+        self.display_state
+            .select(selected.and_then(|i| self.entries.get(i).copied()));
+        f.render_widget(Clear, rect);
+        f.render_stateful_widget(list, rect, &mut self.display_state);
+    }
+
+    fn handle_input(&mut self, key: KeyEvent) -> Result<AppMessage, Box<dyn Error>> {
+        if key_match(&key, &self.keys.back) {
+            return Ok(AppMessage::InputState(AppState::Normal));
+        } else if key_match(&key, &self.keys.move_up) {
+            self.state.prev();
+        } else if key_match(&key, &self.keys.move_down) {
+            self.state.next();
+        } else if key_match(&key, &self.keys.submit) {
+            if let Some(id) = self.selected_id() {
+                return Ok(AppMessage::JumpToTodo(id));
+            }
+        }
+        Ok(AppMessage::NoAction)
+    }
+}
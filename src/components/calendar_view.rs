@@ -0,0 +1,290 @@
+use super::todo_list::{Todo, TodoId};
+use crate::app::{AppMessage, AppState};
+use crate::keys::keymap::SharedKeyList;
+use crate::theme::theme::SharedTheme;
+use crate::widgets::calendar::{Calendar, CalendarState, DayMark};
+use chrono::{Datelike, Local, NaiveDate};
+use crossterm::event::KeyEvent;
+use ratatui::backend::Backend;
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::Style;
+use ratatui::text::{Span, Spans};
+use ratatui::widgets::{Block, Borders, Clear, List, ListItem};
+use ratatui::Frame;
+use std::collections::HashMap;
+use std::error::Error;
+use tui_utils::component::Component;
+use tui_utils::keys::key_match;
+use tui_utils::state::{Boundary, BoundedState, StateWrap};
+use tui_utils::LIST_HIGHLIGHT_SYMBOL;
+
+/// The month at a glance, with the todos due on each day.
+pub struct CalendarViewComponent {
+    /// Rows at the bottom of the screen kept free for the hint bar.
+    pub hints_height: u16,
+    calendar: Calendar,
+    state: CalendarState,
+    // the todos with a due date
+    todos: Vec<Todo>,
+    day: BoundedState,
+    focus: Focus,
+    // the todo being moved to another day
+    rescheduling: Option<TodoId>,
+    keys: SharedKeyList,
+    theme: SharedTheme,
+}
+
+#[derive(PartialEq, Eq)]
+enum Focus {
+    Calendar,
+    Day,
+}
+
+impl CalendarViewComponent {
+    pub fn new(keys: SharedKeyList, theme: SharedTheme) -> Self {
+        Self {
+            hints_height: 0,
+            calendar: Calendar::default(),
+            state: CalendarState::today(),
+            todos: Vec::new(),
+            day: BoundedState::default(),
+            focus: Focus::Calendar,
+            rescheduling: None,
+            keys,
+            theme,
+        }
+    }
+
+    pub fn open(&mut self, todos: &[Todo]) {
+        self.state.go_to_today();
+        self.focus = Focus::Calendar;
+        self.rescheduling = None;
+        self.refresh(todos);
+    }
+
+    /// Picks up changes to the todos, staying on the selected day.
+    pub fn refresh(&mut self, todos: &[Todo]) {
+        self.todos = todos
+            .iter()
+            .filter(|t| t.metadata.due_date.is_some())
+            .cloned()
+            .collect();
+        self.todos.sort_by_key(|t| t.metadata.due_date);
+        self.select_day();
+    }
+
+    fn due_on(&self, date: NaiveDate) -> Vec<&Todo> {
+        self.todos
+            .iter()
+            .filter(|t| t.metadata.due_date.map(|d| d.date()) == Some(date))
+            .collect()
+    }
+
+    fn select_day(&mut self) {
+        let due = self.due_on(self.state.selected()).len();
+        self.day = BoundedState::new(Boundary::from(&vec![(); due]), StateWrap::Enable);
+        if due > 0 {
+            self.day.first();
+        } else {
+            self.focus = Focus::Calendar;
+        }
+    }
+
+    fn selected_todo(&self) -> Option<&Todo> {
+        let i = self.day.inner().selected()?;
+        self.due_on(self.state.selected()).get(i).copied()
+    }
+
+    fn is_overdue(t: &Todo) -> bool {
+        let now = Local::now().naive_local();
+        !t.metadata.finished && t.metadata.due_date.is_some_and(|d| d < now)
+    }
+
+    /// Counts the open todos due on each day of the shown month.
+    fn marks(&self) -> HashMap<u32, DayMark> {
+        let (y, m) = self.state.month().ym();
+        let mut marks = HashMap::new();
+        for t in self.todos.iter() {
+            let Some(due) = t.metadata.due_date else {
+                continue;
+            };
+            if (due.year(), due.month()) != (y, m) {
+                continue;
+            }
+            let entry = marks.entry(due.day()).or_insert((0, 0, false));
+            entry.0 += 1;
+            if !t.metadata.finished {
+                entry.1 += 1;
+            }
+            entry.2 |= Self::is_overdue(t);
+        }
+        marks
+            .into_iter()
+            .map(|(day, (all, open, overdue))| {
+                let mark = if open == 0 {
+                    DayMark {
+                        label: format!("✓{all}"),
+                        color: self.theme.completed_todo_title,
+                    }
+                } else {
+                    DayMark {
+                        label: format!("•{open}"),
+                        color: if overdue {
+                            self.theme.due_overdue
+                        } else {
+                            self.theme.due_soon
+                        },
+                    }
+                };
+                (day, mark)
+            })
+            .collect()
+    }
+
+    fn todo_item(&self, t: &Todo) -> ListItem<'static> {
+        let time = t
+            .metadata
+            .due_date
+            .map(|d| d.format("%-I:%M %P").to_string())
+            .unwrap_or_default();
+        let (check, color) = if t.metadata.finished && !t.metadata.is_recurring() {
+            ("[x]", self.theme.completed_todo_title)
+        } else if Self::is_overdue(t) {
+            ("[ ]", self.theme.due_overdue)
+        } else {
+            ("[ ]", self.theme.todo_title)
+        };
+        ListItem::new(Spans::from(vec![
+            Span::styled(
+                format!("{time:>8} "),
+                Style::default().fg(self.theme.border),
+            ),
+            Span::styled(format!("{check} {}", t.name), Style::default().fg(color)),
+        ]))
+    }
+}
+
+impl Component for CalendarViewComponent {
+    type Message = AppMessage;
+
+    fn draw<B: Backend>(&mut self, f: &mut Frame<B>, _dim: bool) {
+        let size = f.size();
+        let rect = Rect {
+            height: size.height.saturating_sub(self.hints_height),
+            ..size
+        };
+        let chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(60), Constraint::Percentage(40)].as_ref())
+            .split(rect);
+
+        let focused = Style::default().fg(self.theme.move_mode_border);
+        let unfocused = Style::default().fg(self.theme.border);
+
+        let moving = self
+            .rescheduling
+            .and_then(|id| self.todos.iter().find(|t| t.id == id));
+        let (title, border) = match moving {
+            Some(t) => (format!("Move '{}' to", t.name), focused),
+            None if self.focus == Focus::Calendar => ("Calendar".to_string(), focused),
+            None => ("Calendar".to_string(), unfocused),
+        };
+        self.calendar.block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(border)
+                .title(title),
+        );
+        self.calendar.marks(self.marks());
+
+        let date = self.state.selected();
+        let due = self.due_on(date);
+        let items = due.iter().map(|t| self.todo_item(t)).collect::<Vec<_>>();
+        let title = match due.len() {
+            0 => format!("{} - nothing due", date.format("%a %b %-d %Y")),
+            1 => format!("{} - 1 todo", date.format("%a %b %-d %Y")),
+            n => format!("{} - {n} todos", date.format("%a %b %-d %Y")),
+        };
+        let day_border = if self.focus == Focus::Day {
+            focused
+        } else {
+            unfocused
+        };
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(day_border)
+                    .title(title),
+            )
+            .highlight_style(if self.focus == Focus::Day {
+                Style::default().bg(self.theme.selected_bg)
+            } else {
+                Style::default()
+            })
+            .highlight_symbol(LIST_HIGHLIGHT_SYMBOL);
+
+        f.render_widget(Clear, rect);
+        f.render_stateful_widget(self.calendar.clone(), chunks[0], &mut self.state);
+        f.render_stateful_widget(list, chunks[1], self.day.inner_mut());
+    }
+
+    fn handle_input(&mut self, key: KeyEvent) -> Result<AppMessage, Box<dyn Error>> {
+        if self.focus == Focus::Day {
+            if key_match(&key, &self.keys.back) {
+                self.focus = Focus::Calendar;
+            } else if key_match(&key, &self.keys.move_up) {
+                self.day.prev();
+            } else if key_match(&key, &self.keys.move_down) {
+                self.day.next();
+            } else if key_match(&key, &self.keys.submit) {
+                if let Some(t) = self.selected_todo() {
+                    return Ok(AppMessage::JumpToTodo(t.id));
+                }
+            } else if key_match(&key, &self.keys.move_mode) {
+                if let Some(t) = self.selected_todo() {
+                    self.rescheduling = Some(t.id);
+                    self.focus = Focus::Calendar;
+                }
+            }
+            return Ok(AppMessage::NoAction);
+        }
+
+        if key_match(&key, &self.keys.back) {
+            if self.rescheduling.take().is_none() {
+                return Ok(AppMessage::InputState(AppState::Normal));
+            }
+            return Ok(AppMessage::NoAction);
+        } else if key_match(&key, &self.keys.submit) {
+            if let Some(id) = self.rescheduling.take() {
+                return Ok(AppMessage::Reschedule(id, self.state.selected()));
+            }
+            if !self.due_on(self.state.selected()).is_empty() {
+                self.focus = Focus::Day;
+            }
+            return Ok(AppMessage::NoAction);
+        } else if key_match(&key, &self.keys.move_up) {
+            self.state.up();
+        } else if key_match(&key, &self.keys.move_down) {
+            self.state.down();
+        } else if key_match(&key, &self.keys.move_left) {
+            self.state.left();
+        } else if key_match(&key, &self.keys.move_right) {
+            self.state.right();
+        } else if key_match(&key, &self.keys.alt_move_left) {
+            self.state.prev_month();
+        } else if key_match(&key, &self.keys.alt_move_right) {
+            self.state.next_month();
+        } else if key_match(&key, &self.keys.prev_year) {
+            self.state.prev_year();
+        } else if key_match(&key, &self.keys.next_year) {
+            self.state.next_year();
+        } else if key_match(&key, &self.keys.go_to_today) {
+            self.state.go_to_today();
+        } else {
+            return Ok(AppMessage::NoAction);
+        }
+        self.select_day();
+        Ok(AppMessage::NoAction)
+    }
+}
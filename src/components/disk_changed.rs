@@ -0,0 +1,114 @@
+use super::todo_list::DiskChoice;
+use crate::app::AppMessage;
+use crate::keys::keymap::SharedKeyList;
+use crate::merge::Prefer;
+use crate::theme::theme::SharedTheme;
+use crossterm::event::KeyEvent;
+use ratatui::backend::Backend;
+use ratatui::layout::{Constraint, Direction, Layout};
+use ratatui::style::Style;
+use ratatui::widgets::{Block, Borders, Clear, List, ListItem, Paragraph, Wrap};
+use ratatui::Frame;
+use std::error::Error;
+use tui_utils::component::Component;
+use tui_utils::keys::key_match;
+use tui_utils::rect::centered_rect;
+use tui_utils::state::{Boundary, BoundedState, StateWrap};
+use tui_utils::LIST_HIGHLIGHT_SYMBOL;
+
+static CHOICES: [(&str, DiskChoice); 3] = [
+    (
+        "Merge, keeping my version",
+        DiskChoice::Merge(Prefer::Local),
+    ),
+    (
+        "Merge, keeping the version on disk",
+        DiskChoice::Merge(Prefer::Disk),
+    ),
+    ("Reload, dropping my changes", DiskChoice::Reload),
+];
+
+/// Asks how to merge changes made to the list file elsewhere that conflict with local ones.
+pub struct DiskChangedComponent {
+    state: BoundedState,
+    names: Vec<String>,
+    keys: SharedKeyList,
+    theme: SharedTheme,
+}
+
+impl DiskChangedComponent {
+    pub fn new(keys: SharedKeyList, theme: SharedTheme) -> Self {
+        Self {
+            state: BoundedState::default(),
+            names: Vec::new(),
+            keys,
+            theme,
+        }
+    }
+
+    pub fn open(&mut self, names: &[String]) {
+        self.state = BoundedState::new(Boundary::from(&CHOICES.to_vec()), StateWrap::Enable);
+        self.state.first();
+        self.names = names.to_vec();
+    }
+}
+
+impl Component for DiskChangedComponent {
+    type Message = AppMessage;
+
+    fn draw<B: Backend>(&mut self, f: &mut Frame<B>, _dim: bool) {
+        let rect = centered_rect(f.size());
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(5), Constraint::Min(0)].as_ref())
+            .split(rect);
+
+        let names = self
+            .names
+            .iter()
+            .map(|n| format!("'{n}'"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let message = Paragraph::new(format!(
+            "The list was changed elsewhere while you changed {names} here."
+        ))
+        .wrap(Wrap { trim: true })
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(self.theme.border))
+                .title("List changed on disk"),
+        );
+
+        let items = List::new(
+            CHOICES
+                .iter()
+                .map(|(label, _)| ListItem::new(*label))
+                .collect::<Vec<_>>(),
+        )
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(self.theme.border)),
+        )
+        .highlight_style(Style::default().bg(self.theme.selected_bg))
+        .highlight_symbol(LIST_HIGHLIGHT_SYMBOL);
+
+        f.render_widget(Clear, rect);
+        f.render_widget(message, chunks[0]);
+        f.render_stateful_widget(items, chunks[1], self.state.inner_mut());
+    }
+
+    fn handle_input(&mut self, key: KeyEvent) -> Result<AppMessage, Box<dyn Error>> {
+        if key_match(&key, &self.keys.move_up) {
+            self.state.prev();
+        } else if key_match(&key, &self.keys.move_down) {
+            self.state.next();
+        } else if key_match(&key, &self.keys.submit) {
+            if let Some(i) = self.state.inner().selected() {
+                return Ok(AppMessage::ResolveDiskConflict(CHOICES[i].1));
+            }
+        }
+        Ok(AppMessage::NoAction)
+    }
+}
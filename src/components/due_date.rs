@@ -1,7 +1,6 @@
 use anyhow::Result;
-use chrono::{Datelike, NaiveDate, NaiveDateTime, NaiveTime, Timelike};
+use chrono::{NaiveDateTime, NaiveTime, Timelike};
 use crossterm::event::KeyEvent;
-use kanal::Sender;
 use ratatui::{
     backend::Backend,
     layout::Rect,
@@ -22,7 +21,7 @@ use crate::{
     },
 };
 
-use super::{notification::FlashMsg, utils};
+use super::utils;
 
 pub struct DueDateComponent {
     pub calendar: Calendar,
@@ -32,7 +31,6 @@ pub struct DueDateComponent {
     focused_widget: DueDateWidgetHasFocus,
     keys: SharedKeyList,
     theme: SharedTheme,
-    flash_tx: Sender<FlashMsg>,
 }
 
 enum DueDateWidgetHasFocus {
@@ -41,70 +39,33 @@ enum DueDateWidgetHasFocus {
 }
 
 impl DueDateComponent {
-    pub fn new(keys: SharedKeyList, theme: SharedTheme, flash_tx: Sender<FlashMsg>) -> Self {
-        let calendar = Calendar::default();
-
-        let (day, num_days) = if let Some(month) = calendar.current_month() {
-            (month.default_day(), month.num_days())
-        } else {
-            (1, 31)
-        };
-
+    pub fn new(keys: SharedKeyList, theme: SharedTheme) -> Self {
         Self {
-            calendar,
-            calendar_state: CalendarState::new(day, num_days),
+            calendar: Calendar::default(),
+            calendar_state: CalendarState::today(),
             time_picker: TimePicker::default(),
             time_picker_state: TimePickerState::with_current_time(),
             focused_widget: DueDateWidgetHasFocus::Cal,
             keys,
             theme,
-            flash_tx,
         }
     }
 
     pub fn get_date_time(&self) -> NaiveDateTime {
-        let month_index = self.calendar_state.selected_month();
-        let month = self.calendar.get_month_by_index(month_index).unwrap();
-        let (y, mo) = month.ym();
-        let d = self.calendar_state.selected_day();
-
         let (h, mi) = self.time_picker_state.hour_minute();
         // FIXME: handle OOB failure
-        let date = NaiveDate::from_ymd_opt(y, mo, d).unwrap();
         let time = NaiveTime::from_hms_opt(h, mi, 0).unwrap();
-        NaiveDateTime::new(date, time)
+        NaiveDateTime::new(self.calendar_state.selected(), time)
     }
 
-    pub fn reset_date_time(&mut self) -> Result<()> {
-        if let Some(current_month) = self.calendar.current_month() {
-            let today = current_month.default_day();
-            if let Err(e) = self.calendar_state.set_date(today) {
-                self.flash_tx.send(FlashMsg::err(e))?;
-            }
-            self.time_picker_state = TimePickerState::with_current_time();
-        }
-        Ok(())
+    pub fn reset_date_time(&mut self) {
+        self.calendar_state.go_to_today();
+        self.time_picker_state = TimePickerState::with_current_time();
     }
 
-    pub fn set_date_time(&mut self, dt: NaiveDateTime) -> Result<()> {
-        let date = dt.date();
-        let month = date.month0();
-
-        if let Some((i, m)) = self.calendar.get_month_and_index_by_num(month as usize) {
-            let day = date.day0();
-            let num_days = m.num_days();
-
-            let time = dt.time();
-            let hour = time.hour();
-            let minute = time.minute();
-
-            self.time_picker_state = TimePickerState::with_hm(hour, minute);
-            match CalendarState::with_date(i, day as usize, num_days) {
-                Ok(state) => self.calendar_state = state,
-                Err(e) => self.flash_tx.send(FlashMsg::err(e))?,
-            }
-        }
-        Ok(())
+    pub fn set_date_time(&mut self, dt: NaiveDateTime) {
+        self.calendar_state.select(dt.date());
+        self.time_picker_state = TimePickerState::with_hm(dt.hour(), dt.minute());
     }
 }
 
@@ -174,20 +135,14 @@ impl Component for DueDateComponent {
                     self.focused_widget = DueDateWidgetHasFocus::Time;
                 } else if key_match(&key, &self.keys.alt_move_left) {
                     self.calendar_state.prev_month();
-                    if let Some(m) = self
-                        .calendar
-                        .get_month_by_index(self.calendar_state.selected_month())
-                    {
-                        self.calendar_state.set_num_days(m.num_days());
-                    }
                 } else if key_match(&key, &self.keys.alt_move_right) {
                     self.calendar_state.next_month();
-                    if let Some(m) = self
-                        .calendar
-                        .get_month_by_index(self.calendar_state.selected_month())
-                    {
-                        self.calendar_state.set_num_days(m.num_days());
-                    }
+                } else if key_match(&key, &self.keys.prev_year) {
+                    self.calendar_state.prev_year();
+                } else if key_match(&key, &self.keys.next_year) {
+                    self.calendar_state.next_year();
+                } else if key_match(&key, &self.keys.go_to_today) {
+                    self.calendar_state.go_to_today();
                 }
             }
             DueDateWidgetHasFocus::Time => {
@@ -206,14 +161,14 @@ impl Component for DueDateComponent {
         }
         // this should always be handled no matter the focus
         if key_match(&key, &self.keys.back) {
-            self.reset_date_time()?;
+            self.reset_date_time();
             // set to AddTodo since it just changes the state
             // while EditTodo copies the currently selected todo's
             // contents into the edit view fields
             return Ok(AppMessage::InputState(AppState::AddTodo));
         } else if key_match(&key, &self.keys.submit) {
             let date_time = self.get_date_time();
-            self.reset_date_time()?;
+            self.reset_date_time();
             return Ok(AppMessage::SetDueDate(date_time));
         }
         Ok(AppMessage::NoAction)
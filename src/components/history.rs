@@ -0,0 +1,88 @@
+use super::todo_list::{Todo, TodoId};
+
+static HISTORY_LIMIT: usize = 100;
+
+/// A single reversible mutation of the todo list.
+#[derive(Clone, Debug)]
+pub enum ListOp {
+    Add {
+        todo: Todo,
+        index: usize,
+    },
+    Remove {
+        todo: Todo,
+        index: usize,
+    },
+    Replace {
+        before: Todo,
+        after: Todo,
+    },
+    /// A move mode session, stored as the order of ids before and after it.
+    Reorder {
+        before: Vec<TodoId>,
+        after: Vec<TodoId>,
+    },
+}
+
+impl ListOp {
+    pub fn inverse(self) -> Self {
+        match self {
+            Self::Add { todo, index } => Self::Remove { todo, index },
+            Self::Remove { todo, index } => Self::Add { todo, index },
+            Self::Replace { before, after } => Self::Replace {
+                before: after,
+                after: before,
+            },
+            Self::Reorder { before, after } => Self::Reorder {
+                before: after,
+                after: before,
+            },
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Add { todo, .. } => format!("add '{}'", todo.name),
+            Self::Remove { todo, .. } => format!("remove '{}'", todo.name),
+            // toggling a todo (or completing an occurrence) also replaces it
+            Self::Replace { before, after }
+                if before.metadata.finished != after.metadata.finished
+                    || before.metadata.last_completed != after.metadata.last_completed =>
+            {
+                format!("toggle '{}'", after.name)
+            }
+            Self::Replace { after, .. } => format!("edit '{}'", after.name),
+            Self::Reorder { .. } => "move".into(),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct History {
+    undo: Vec<ListOp>,
+    redo: Vec<ListOp>,
+}
+
+impl History {
+    pub fn record(&mut self, op: ListOp) {
+        if self.undo.len() == HISTORY_LIMIT {
+            self.undo.remove(0);
+        }
+        self.undo.push(op);
+        self.redo.clear();
+    }
+
+    /// Pops the last operation so it can be reverted with `ListOp::inverse`.
+    pub fn undo(&mut self) -> Option<ListOp> {
+        let op = self.undo.pop()?;
+        self.redo.push(op.clone());
+        Some(op)
+    }
+
+    /// Pops the last undone operation, returning it so it can be applied again.
+    pub fn redo(&mut self) -> Option<ListOp> {
+        let op = self.redo.pop()?;
+        self.undo.push(op.clone());
+        Some(op)
+    }
+}
@@ -0,0 +1,289 @@
+use super::notification::FlashMsg;
+use crate::app::{AppMessage, AppState};
+use crate::keys::keymap::SharedKeyList;
+use crate::store;
+use crate::theme::theme::SharedTheme;
+use anyhow::Result;
+use crossterm::event::{Event, KeyEvent};
+use kanal::Sender;
+use ratatui::backend::Backend;
+use ratatui::layout::{Constraint, Direction, Layout};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Span, Spans};
+use ratatui::widgets::{Block, Borders, Clear, List, ListItem, Paragraph};
+use ratatui::Frame;
+use std::error::Error;
+use tui_input::backend::crossterm as input_backend;
+use tui_input::Input;
+use tui_utils::component::Component;
+use tui_utils::keys::key_match;
+use tui_utils::rect::centered_rect;
+use tui_utils::state::{Boundary, BoundedState, StateWrap};
+use tui_utils::LIST_HIGHLIGHT_SYMBOL;
+
+pub enum ListsAction {
+    Switch(String),
+    MoveTodo(String),
+    Renamed { from: String, to: String },
+}
+
+/// What selecting a list in the switcher does.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum SwitcherPurpose {
+    Switch,
+    MoveTodo,
+}
+
+#[derive(PartialEq, Eq)]
+enum SwitcherMode {
+    Browse,
+    Create,
+    Rename,
+}
+
+pub struct ListSwitcherComponent {
+    pub state: BoundedState,
+    pub lists: Vec<String>,
+    pub input: Input,
+    current: String,
+    purpose: SwitcherPurpose,
+    mode: SwitcherMode,
+    // the list waiting for a second press of the delete key
+    confirm_delete: Option<String>,
+    keys: SharedKeyList,
+    theme: SharedTheme,
+    flash_tx: Sender<FlashMsg>,
+}
+
+impl ListSwitcherComponent {
+    pub fn new(keys: SharedKeyList, theme: SharedTheme, flash_tx: Sender<FlashMsg>) -> Self {
+        Self {
+            state: BoundedState::default(),
+            lists: Vec::new(),
+            input: Input::default(),
+            current: String::new(),
+            purpose: SwitcherPurpose::Switch,
+            mode: SwitcherMode::Browse,
+            confirm_delete: None,
+            keys,
+            theme,
+            flash_tx,
+        }
+    }
+
+    pub fn open(&mut self, current: &str, purpose: SwitcherPurpose) -> Result<()> {
+        self.current = current.to_string();
+        self.purpose = purpose;
+        self.mode = SwitcherMode::Browse;
+        self.confirm_delete = None;
+        self.input.reset();
+        self.refresh(current)
+    }
+
+    /// Reloads the list names and selects `selection` if it exists.
+    fn refresh(&mut self, selection: &str) -> Result<()> {
+        self.lists = store::list_names()?;
+        self.state = BoundedState::new(Boundary::from(&self.lists), StateWrap::Enable);
+        let i = self.lists.iter().position(|l| l == selection).unwrap_or(0);
+        self.state.select(i).unwrap();
+        Ok(())
+    }
+
+    pub fn selected_list(&self) -> Option<&String> {
+        self.state.inner().selected().map(|i| &self.lists[i])
+    }
+
+    fn report<T>(&self, res: Result<T>) -> Result<Option<T>> {
+        match res {
+            Ok(t) => Ok(Some(t)),
+            Err(e) => {
+                self.flash_tx.send(FlashMsg::err(e))?;
+                Ok(None)
+            }
+        }
+    }
+
+    fn submit_input(&mut self) -> Result<AppMessage> {
+        let name = self.input.value().trim().to_string();
+        match self.mode {
+            SwitcherMode::Create => {
+                if self.report(store::create_list(&name))?.is_some() {
+                    self.flash_tx
+                        .send(FlashMsg::info(format!("Created list {name}")))?;
+                    self.mode = SwitcherMode::Browse;
+                    self.input.reset();
+                    self.refresh(&name)?;
+                }
+            }
+            SwitcherMode::Rename => {
+                let from = self.selected_list().cloned().unwrap_or_default();
+                if self.report(store::rename_list(&from, &name))?.is_some() {
+                    self.mode = SwitcherMode::Browse;
+                    self.input.reset();
+                    self.refresh(&name)?;
+                    if from == self.current {
+                        self.current = name.clone();
+                    }
+                    return Ok(AppMessage::Lists(ListsAction::Renamed { from, to: name }));
+                }
+            }
+            SwitcherMode::Browse => {}
+        }
+        Ok(AppMessage::NoAction)
+    }
+
+    fn delete_selected(&mut self) -> Result<()> {
+        let Some(name) = self.selected_list().cloned() else {
+            return Ok(());
+        };
+        if name == self.current {
+            self.flash_tx
+                .send(FlashMsg::err("Can't delete the list that is open"))?;
+            return Ok(());
+        }
+        if self.confirm_delete.as_ref() != Some(&name) {
+            self.flash_tx.send(FlashMsg::warn(format!(
+                "Press {} again to delete list {name}",
+                self.keys.remove_todo
+            )))?;
+            self.confirm_delete = Some(name);
+            return Ok(());
+        }
+        self.confirm_delete = None;
+        if let Some(deleted) = self.report(store::delete_list(&name))? {
+            self.flash_tx.send(FlashMsg::info(format!(
+                "Deleted list {name}, its todos are kept in {}",
+                deleted.display()
+            )))?;
+            let current = self.current.clone();
+            self.refresh(&current)?;
+        }
+        Ok(())
+    }
+}
+
+impl Component for ListSwitcherComponent {
+    type Message = AppMessage;
+
+    fn draw<B: Backend>(&mut self, f: &mut Frame<B>, _dim: bool) {
+        let rect = centered_rect(f.size());
+
+        let title = match (&self.confirm_delete, self.purpose) {
+            (Some(name), _) => format!("Delete {name}? [{}]", self.keys.remove_todo),
+            (None, SwitcherPurpose::Switch) => "Lists".to_string(),
+            (None, SwitcherPurpose::MoveTodo) => "Move todo to list".to_string(),
+        };
+
+        let list_items: Vec<ListItem> = self
+            .lists
+            .iter()
+            .map(|l| {
+                let mut spans = vec![Span::raw(l.as_str())];
+                if *l == self.current {
+                    spans.push(Span::styled(
+                        " (open)",
+                        Style::default()
+                            .fg(self.theme.selected_fg)
+                            .add_modifier(Modifier::BOLD),
+                    ));
+                }
+                ListItem::new(Spans::from(spans))
+            })
+            .collect();
+
+        let items = List::new(list_items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(self.theme.border))
+                    .title(title),
+            )
+            .highlight_style(Style::default().bg(self.theme.selected_bg))
+            .highlight_symbol(LIST_HIGHLIGHT_SYMBOL);
+
+        if self.mode == SwitcherMode::Browse {
+            f.render_widget(Clear, rect);
+            f.render_stateful_widget(items, rect, self.state.inner_mut());
+            return;
+        }
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(3), Constraint::Min(3)].as_ref())
+            .split(rect);
+
+        let input_title = if self.mode == SwitcherMode::Create {
+            "New list"
+        } else {
+            "Rename list"
+        };
+        let width = chunks[0].width.max(3) - 3;
+        let scroll = (self.input.cursor() as u16).max(width) - width;
+        let name_input = Paragraph::new(self.input.value())
+            .scroll((0, scroll))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(self.theme.border))
+                    .title(input_title),
+            );
+
+        f.render_widget(Clear, chunks[0]);
+        f.render_widget(Clear, chunks[1]);
+
+        f.render_widget(name_input, chunks[0]);
+        f.render_stateful_widget(items, chunks[1], self.state.inner_mut());
+        f.set_cursor(
+            chunks[0].x + (self.input.cursor() as u16).min(width) + 1,
+            chunks[0].y + 1,
+        );
+    }
+
+    fn handle_input(&mut self, key: KeyEvent) -> Result<AppMessage, Box<dyn Error>> {
+        if self.mode != SwitcherMode::Browse {
+            if key_match(&key, &self.keys.back) {
+                self.mode = SwitcherMode::Browse;
+                self.input.reset();
+            } else if key_match(&key, &self.keys.submit) {
+                return Ok(self.submit_input()?);
+            } else {
+                input_backend::to_input_request(Event::Key(key)).and_then(|r| self.input.handle(r));
+            }
+            return Ok(AppMessage::NoAction);
+        }
+
+        if key_match(&key, &self.keys.remove_todo) {
+            self.delete_selected()?;
+            return Ok(AppMessage::NoAction);
+        }
+        // any other key cancels a pending delete
+        if self.confirm_delete.take().is_some() {
+            self.flash_tx.send(FlashMsg::info("Kept the list"))?;
+            return Ok(AppMessage::NoAction);
+        }
+
+        if key_match(&key, &self.keys.back) {
+            return Ok(AppMessage::InputState(AppState::Normal));
+        } else if key_match(&key, &self.keys.move_up) {
+            self.state.prev();
+        } else if key_match(&key, &self.keys.move_down) {
+            self.state.next();
+        } else if key_match(&key, &self.keys.submit) {
+            if let Some(name) = self.selected_list().cloned() {
+                let action = match self.purpose {
+                    SwitcherPurpose::Switch => ListsAction::Switch(name),
+                    SwitcherPurpose::MoveTodo => ListsAction::MoveTodo(name),
+                };
+                return Ok(AppMessage::Lists(action));
+            }
+        } else if key_match(&key, &self.keys.add_todo) {
+            self.mode = SwitcherMode::Create;
+        } else if key_match(&key, &self.keys.edit_todo) {
+            if let Some(name) = self.selected_list() {
+                self.input = Input::from(name.clone());
+                self.mode = SwitcherMode::Rename;
+            }
+        }
+        Ok(AppMessage::NoAction)
+    }
+}
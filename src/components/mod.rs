@@ -1,11 +1,26 @@
+pub mod agenda;
+pub mod calendar_view;
+pub mod disk_changed;
 pub mod due_date;
+pub mod history;
+pub mod list_switcher;
 pub mod notification;
 pub mod skimmer;
+pub mod subtask_input;
+pub mod sync_conflict;
+pub mod tag_filter;
 pub mod todo_input;
 pub mod todo_list;
 pub mod utils;
 
+pub use agenda::AgendaComponent;
+pub use calendar_view::CalendarViewComponent;
+pub use disk_changed::DiskChangedComponent;
+pub use list_switcher::ListSwitcherComponent;
 pub use notification::NotificationComponent;
 pub use skimmer::SkimmerComponent;
+pub use subtask_input::SubtaskInputComponent;
+pub use sync_conflict::SyncConflictComponent;
+pub use tag_filter::TagFilterComponent;
 pub use todo_input::TodoInputComponent;
 pub use todo_list::TodoListComponent;
@@ -1,4 +1,4 @@
-use super::todo_list::Todo;
+use super::todo_list::{Todo, TodoId};
 use crate::app::{AppMessage, AppState};
 use crate::keys::keymap::SharedKeyList;
 use crate::theme::theme::SharedTheme;
@@ -23,12 +23,12 @@ use tui_utils::LIST_HIGHLIGHT_SYMBOL;
 
 pub enum SkimmerAction {
     Skim,
-    ReportSelection(usize),
+    ReportSelection(TodoId),
 }
 
 pub struct SkimMatch {
     pub text: String,
-    pub position: usize,
+    pub id: TodoId,
     pub indices: Vec<usize>,
     score: i64,
 }
@@ -42,11 +42,11 @@ pub struct SkimmerComponent {
     matcher: Box<SkimMatcherV2>,
 }
 
-impl From<(usize, &Todo)> for SkimMatch {
-    fn from(other: (usize, &Todo)) -> Self {
+impl From<&Todo> for SkimMatch {
+    fn from(other: &Todo) -> Self {
         Self {
-            text: other.1.name.to_string(),
-            position: other.0,
+            text: other.name.to_string(),
+            id: other.id,
             indices: Vec::new(),
             score: 0,
         }
@@ -73,13 +73,13 @@ impl SkimmerComponent {
 
     pub fn skim(&mut self, todos: &[Todo]) {
         self.matches.clear();
-        for (i, todo) in todos.iter().enumerate() {
+        for todo in todos.iter() {
             if let Some((score, indices)) =
                 self.matcher.fuzzy_indices(&todo.name, self.input.value())
             {
                 let m = SkimMatch {
                     text: todo.name.clone(),
-                    position: i,
+                    id: todo.id,
                     indices,
                     score,
                 };
@@ -189,9 +189,7 @@ impl Component for SkimmerComponent {
             self.next();
         } else if key_match(&key, &self.keys.submit) {
             let response = if let Some(s) = self.selected_match() {
-                Ok(AppMessage::Skimmer(SkimmerAction::ReportSelection(
-                    s.position,
-                )))
+                Ok(AppMessage::Skimmer(SkimmerAction::ReportSelection(s.id)))
             } else {
                 Ok(AppMessage::InputState(AppState::Normal))
             };
@@ -0,0 +1,81 @@
+use super::todo_list::ListAction;
+use crate::app::{AppMessage, AppState};
+use crate::keys::keymap::SharedKeyList;
+use crate::theme::theme::SharedTheme;
+use crossterm::event::{Event, KeyEvent};
+use ratatui::backend::Backend;
+use ratatui::layout::{Constraint, Direction, Layout};
+use ratatui::style::Style;
+use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+use ratatui::Frame;
+use std::error::Error;
+use tui_input::backend::crossterm as input_backend;
+use tui_input::Input;
+use tui_utils::component::Component;
+use tui_utils::keys::key_match;
+use tui_utils::rect::centered_rect;
+
+pub struct SubtaskInputComponent {
+    pub name: Input,
+    keys: SharedKeyList,
+    theme: SharedTheme,
+}
+
+impl SubtaskInputComponent {
+    pub fn new(keys: SharedKeyList, theme: SharedTheme) -> Self {
+        Self {
+            name: Input::default(),
+            keys,
+            theme,
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.name.reset();
+    }
+}
+
+impl Component for SubtaskInputComponent {
+    type Message = AppMessage;
+
+    fn draw<B: Backend>(&mut self, f: &mut Frame<B>, _dim: bool) {
+        let rect = centered_rect(f.size());
+        let chunk = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(3), Constraint::Min(0)].as_ref())
+            .split(rect)[0];
+
+        let width = chunk.width.max(3) - 3;
+        let scroll = (self.name.cursor() as u16).max(width) - width;
+        let name_input = Paragraph::new(self.name.value()).scroll((0, scroll)).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(self.theme.border))
+                .title("New subtask"),
+        );
+
+        f.render_widget(Clear, chunk);
+        f.render_widget(name_input, chunk);
+        f.set_cursor(
+            chunk.x + (self.name.cursor() as u16).min(width) + 1,
+            chunk.y + 1,
+        );
+    }
+
+    fn handle_input(&mut self, key: KeyEvent) -> Result<AppMessage, Box<dyn Error>> {
+        if key_match(&key, &self.keys.back) {
+            self.clear();
+            return Ok(AppMessage::InputState(AppState::Normal));
+        } else if key_match(&key, &self.keys.submit) {
+            let name = self.name.value().trim();
+            if !name.is_empty() {
+                return Ok(AppMessage::UpdateList(ListAction::AddSubtask(
+                    name.to_string(),
+                )));
+            }
+        } else {
+            input_backend::to_input_request(Event::Key(key)).and_then(|r| self.name.handle(r));
+        }
+        Ok(AppMessage::NoAction)
+    }
+}
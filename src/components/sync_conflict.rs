@@ -0,0 +1,158 @@
+use super::todo_list::{Todo, TIME_FORMAT};
+use crate::app::{AppMessage, AppState};
+use crate::keys::keymap::SharedKeyList;
+use crate::sync::{Conflict, Resolution};
+use crate::theme::theme::SharedTheme;
+use crossterm::event::KeyEvent;
+use ratatui::backend::Backend;
+use ratatui::layout::{Constraint, Direction, Layout};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Span, Spans};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph, Wrap};
+use ratatui::Frame;
+use std::error::Error;
+use tui_utils::component::Component;
+use tui_utils::keys::key_match;
+use tui_utils::rect::centered_rect;
+
+/// Shows todos changed both locally and on the server side by side
+/// and lets the user pick which version to keep.
+pub struct SyncConflictComponent {
+    conflicts: Vec<Conflict>,
+    total: usize,
+    keep: Resolution,
+    keys: SharedKeyList,
+    theme: SharedTheme,
+}
+
+impl SyncConflictComponent {
+    pub fn new(keys: SharedKeyList, theme: SharedTheme) -> Self {
+        Self {
+            conflicts: Vec::new(),
+            total: 0,
+            keep: Resolution::KeepLocal,
+            keys,
+            theme,
+        }
+    }
+
+    pub fn open(&mut self, conflicts: Vec<Conflict>) {
+        self.total = conflicts.len();
+        self.conflicts = conflicts;
+        self.keep = Resolution::KeepLocal;
+    }
+
+    pub fn current(&self) -> Option<&Conflict> {
+        self.conflicts.first()
+    }
+
+    /// Drops the current conflict, returning whether any are left.
+    pub fn advance(&mut self) -> bool {
+        if !self.conflicts.is_empty() {
+            self.conflicts.remove(0);
+        }
+        self.keep = Resolution::KeepLocal;
+        !self.conflicts.is_empty()
+    }
+
+    fn pane(&self, title: &'static str, t: &Todo, chosen: bool) -> Paragraph<'static> {
+        let md = &t.metadata;
+        let due = md
+            .due_date
+            .map(|dd| dd.format(TIME_FORMAT).to_string())
+            .unwrap_or_else(|| "not set".into());
+        let status = if md.finished { "finished" } else { "open" };
+
+        let mut lines = vec![
+            Spans::from(Span::styled(
+                t.name.clone(),
+                Style::default()
+                    .fg(self.theme.todo_title)
+                    .add_modifier(Modifier::BOLD),
+            )),
+            Spans::from(format!("Due date: {due}")),
+            Spans::from(format!("Priority: {}", md.priority)),
+            Spans::from(format!("Status: {status}")),
+        ];
+        if !t.tags.is_empty() {
+            let mut tags = vec![Span::raw("Tags: ")];
+            for tag in t.tags.iter() {
+                tags.push(Span::styled(
+                    format!(" {tag} "),
+                    Style::default()
+                        .fg(self.theme.tag_fg)
+                        .bg(self.theme.tag_color(tag)),
+                ));
+                tags.push(Span::raw(" "));
+            }
+            lines.push(Spans::from(tags));
+        }
+        if !t.description.is_empty() {
+            lines.push(Spans::default());
+            lines.extend(t.description.lines().map(|l| Spans::from(l.to_string())));
+        }
+
+        let border = if chosen {
+            self.theme.move_mode_border
+        } else {
+            self.theme.border
+        };
+        Paragraph::new(lines).wrap(Wrap { trim: false }).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(border))
+                .title(title),
+        )
+    }
+}
+
+impl Component for SyncConflictComponent {
+    type Message = AppMessage;
+
+    fn draw<B: Backend>(&mut self, f: &mut Frame<B>, _dim: bool) {
+        let Some(conflict) = self.current() else {
+            return;
+        };
+        let rect = centered_rect(f.size());
+        let title = format!(
+            "Conflict {}/{}",
+            self.total - self.conflicts.len() + 1,
+            self.total
+        );
+        let outer = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(self.theme.border))
+            .title(title);
+        let inner = outer.inner(rect);
+        let chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
+            .split(inner);
+
+        let local = self.pane("Local", &conflict.local, self.keep == Resolution::KeepLocal);
+        let remote = self.pane(
+            "Remote",
+            &conflict.remote,
+            self.keep == Resolution::KeepRemote,
+        );
+
+        f.render_widget(Clear, rect);
+        f.render_widget(outer, rect);
+        f.render_widget(local, chunks[0]);
+        f.render_widget(remote, chunks[1]);
+    }
+
+    fn handle_input(&mut self, key: KeyEvent) -> Result<AppMessage, Box<dyn Error>> {
+        if key_match(&key, &self.keys.back) {
+            // unresolved conflicts come up again on the next sync
+            return Ok(AppMessage::InputState(AppState::Normal));
+        } else if key_match(&key, &self.keys.move_left) {
+            self.keep = Resolution::KeepLocal;
+        } else if key_match(&key, &self.keys.move_right) {
+            self.keep = Resolution::KeepRemote;
+        } else if key_match(&key, &self.keys.submit) {
+            return Ok(AppMessage::ResolveConflict(self.keep));
+        }
+        Ok(AppMessage::NoAction)
+    }
+}
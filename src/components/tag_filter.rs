@@ -0,0 +1,114 @@
+use crate::app::{AppMessage, AppState};
+use crate::keys::keymap::SharedKeyList;
+use crate::theme::theme::SharedTheme;
+use crossterm::event::KeyEvent;
+use ratatui::backend::Backend;
+use ratatui::style::Style;
+use ratatui::text::{Span, Spans};
+use ratatui::widgets::{Block, Borders, Clear, List, ListItem};
+use ratatui::Frame;
+use std::error::Error;
+use tui_utils::component::Component;
+use tui_utils::keys::key_match;
+use tui_utils::rect::centered_rect;
+use tui_utils::state::{Boundary, BoundedState, StateWrap};
+use tui_utils::LIST_HIGHLIGHT_SYMBOL;
+
+pub struct TagFilterComponent {
+    pub state: BoundedState,
+    pub tags: Vec<String>,
+    pub selected: Vec<String>,
+    keys: SharedKeyList,
+    theme: SharedTheme,
+}
+
+impl TagFilterComponent {
+    pub fn new(keys: SharedKeyList, theme: SharedTheme) -> Self {
+        Self {
+            state: BoundedState::default(),
+            tags: Vec::new(),
+            selected: Vec::new(),
+            keys,
+            theme,
+        }
+    }
+
+    pub fn open(&mut self, tags: Vec<String>, active: &[String]) {
+        self.state = BoundedState::new(Boundary::from(&tags), StateWrap::Enable);
+        if !tags.is_empty() {
+            self.state.first();
+        }
+        self.tags = tags;
+        self.selected = active.to_vec();
+    }
+
+    fn toggle_current(&mut self) {
+        let Some(tag) = self.state.inner().selected().map(|i| &self.tags[i]) else {
+            return;
+        };
+        if let Some(pos) = self.selected.iter().position(|t| t == tag) {
+            self.selected.remove(pos);
+        } else {
+            self.selected.push(tag.clone());
+        }
+    }
+}
+
+impl Component for TagFilterComponent {
+    type Message = AppMessage;
+
+    fn draw<B: Backend>(&mut self, f: &mut Frame<B>, _dim: bool) {
+        let rect = centered_rect(f.size());
+
+        let list_items: Vec<ListItem> = self
+            .tags
+            .iter()
+            .map(|t| {
+                let checked = if self.selected.contains(t) {
+                    "[x] "
+                } else {
+                    "[ ] "
+                };
+                ListItem::new(Spans::from(vec![
+                    Span::raw(checked),
+                    Span::styled(
+                        format!(" {t} "),
+                        Style::default()
+                            .fg(self.theme.tag_fg)
+                            .bg(self.theme.tag_color(t)),
+                    ),
+                ]))
+            })
+            .collect();
+
+        let items = List::new(list_items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(self.theme.border))
+                    .title("Filter by tags"),
+            )
+            .highlight_style(Style::default().bg(self.theme.selected_bg))
+            .highlight_symbol(LIST_HIGHLIGHT_SYMBOL);
+
+        f.render_widget(Clear, rect);
+        f.render_stateful_widget(items, rect, self.state.inner_mut());
+    }
+
+    fn handle_input(&mut self, key: KeyEvent) -> Result<AppMessage, Box<dyn Error>> {
+        if key_match(&key, &self.keys.back) {
+            return Ok(AppMessage::InputState(AppState::Normal));
+        } else if key_match(&key, &self.keys.move_up) {
+            self.state.prev();
+        } else if key_match(&key, &self.keys.move_down) {
+            self.state.next();
+        } else if key_match(&key, &self.keys.toggle_completed) {
+            self.toggle_current();
+        } else if key_match(&key, &self.keys.remove_todo) {
+            self.selected.clear();
+        } else if key_match(&key, &self.keys.submit) {
+            return Ok(AppMessage::SetTagFilter(self.selected.clone()));
+        }
+        Ok(AppMessage::NoAction)
+    }
+}
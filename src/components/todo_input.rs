@@ -1,16 +1,25 @@
-use super::todo_list::{ListAction, Todo, TodoMetadata};
+use super::notification::FlashMsg;
+use super::todo_list::{ListAction, Todo, TodoId, TodoMetadata};
+use crate::subtask::Subtask;
 use crate::{
     app::{AppMessage, AppState},
+    due,
     keys::keymap::SharedKeyList,
+    priority::Priority,
+    quick_add::{self, Token},
+    recurrence::Recurrence,
+    tags,
     theme::theme::SharedTheme,
 };
 use anyhow::Result;
 use chrono::{Local, NaiveDateTime};
 use crossterm::event::{Event, KeyEvent};
+use kanal::Sender;
 use ratatui::{
     backend::Backend,
     layout::{Constraint, Direction, Layout},
-    style::Style,
+    style::{Modifier, Style},
+    text::{Span, Spans},
     widgets::{Block, Borders, Clear, Paragraph, Wrap},
     Frame,
 };
@@ -19,16 +28,54 @@ use tui_input::backend::crossterm as input_backend;
 use tui_input::Input;
 use tui_utils::{component::Component, keys::key_match, rect::centered_rect};
 
+// written back into the due field, so it has to parse
+static DUE_FORMAT: &str = "%Y-%m-%d %H:%M";
+static PREVIEW_FORMAT: &str = "%a %b %-d %Y, %-I:%M %P";
+
 #[derive(Clone)]
 pub struct TodoInputComponent {
     pub name: Input,
+    pub due: Input,
+    pub recurrence: Input,
+    pub tags: Input,
     pub description: String,
     pub finished: bool,
     pub metadata: TodoMetadata,
+    pub subtasks: Vec<Subtask>,
     pub is_editing_existing: bool,
-    todo_index: usize,
+    todo_id: TodoId,
+    focused_field: InputField,
     keys: SharedKeyList,
     theme: SharedTheme,
+    flash_tx: Sender<FlashMsg>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum InputField {
+    Name,
+    Due,
+    Recurrence,
+    Tags,
+}
+
+impl InputField {
+    fn next(self) -> Self {
+        match self {
+            Self::Name => Self::Due,
+            Self::Due => Self::Recurrence,
+            Self::Recurrence => Self::Tags,
+            Self::Tags => Self::Name,
+        }
+    }
+
+    fn prev(self) -> Self {
+        match self {
+            Self::Name => Self::Tags,
+            Self::Due => Self::Name,
+            Self::Recurrence => Self::Due,
+            Self::Tags => Self::Recurrence,
+        }
+    }
 }
 
 impl From<TodoInputComponent> for Todo {
@@ -40,51 +87,175 @@ impl From<TodoInputComponent> for Todo {
         };
 
         Self {
+            id: other.todo_id,
             name: other.name.value().to_string(),
             description: other.description,
             metadata: TodoMetadata {
                 edited_at,
                 ..other.metadata
             },
+            subtasks: other.subtasks,
+            tags: tags::parse(other.tags.value()),
         }
     }
 }
 
 impl TodoInputComponent {
-    pub fn new(keys: SharedKeyList, theme: SharedTheme) -> Self {
+    pub fn new(keys: SharedKeyList, theme: SharedTheme, flash_tx: Sender<FlashMsg>) -> Self {
         Self {
             name: Input::default(),
+            due: Input::default(),
+            recurrence: Input::default(),
+            tags: Input::default(),
             description: String::default(),
             finished: false,
             metadata: TodoMetadata::default(),
+            subtasks: Vec::new(),
             is_editing_existing: false,
-            todo_index: 0,
+            todo_id: 0,
+            focused_field: InputField::Name,
             keys,
             theme,
+            flash_tx,
         }
     }
 
-    pub fn populate_with(&mut self, todo: &Todo, i: usize) {
+    pub fn populate_with(&mut self, todo: &Todo) {
         self.name = Input::from(todo.name.clone());
+        self.due = match todo.metadata.due_date {
+            Some(dt) => Input::from(dt.format(DUE_FORMAT).to_string()),
+            None => Input::default(),
+        };
+        self.recurrence = match &todo.metadata.recurrence {
+            Some(rule) => Input::from(rule.to_string()),
+            None => Input::default(),
+        };
+        self.tags = Input::from(tags::format(&todo.tags));
         self.description = todo.description.to_string();
         self.metadata = todo.metadata.clone();
+        self.subtasks = todo.subtasks.clone();
         self.is_editing_existing = true;
-        self.todo_index = i;
+        self.todo_id = todo.id;
     }
 
     pub fn set_due_date(&mut self, dt: NaiveDateTime) {
         self.metadata.due_date = Some(dt);
+        self.due = Input::from(dt.format(DUE_FORMAT).to_string());
     }
 
+    /// The due date typed so far, or the last valid one if the field does not parse.
     pub fn get_due_date(&self) -> Option<NaiveDateTime> {
-        self.metadata.due_date
+        self.parse_due().unwrap_or(self.metadata.due_date)
+    }
+
+    /// Parses the due field, an empty field means no due date.
+    fn parse_due(&self) -> Result<Option<NaiveDateTime>> {
+        let due = self.due.value().trim();
+        if due.is_empty() {
+            return Ok(None);
+        }
+        due::parse(due, Local::now().naive_local()).map(Some)
+    }
+
+    /// Parses the recurrence field into `metadata`, an empty field means no recurrence.
+    fn apply_recurrence(&mut self) -> Result<()> {
+        let rule = self.recurrence.value().trim();
+        self.metadata.recurrence = if rule.is_empty() {
+            None
+        } else {
+            Some(rule.parse::<Recurrence>()?)
+        };
+        Ok(())
+    }
+
+    /// Whether the name is empty, or for new todos only made of quick-add tokens.
+    fn missing_name(&self) -> bool {
+        if self.is_editing_existing {
+            return self.name.value().trim().is_empty();
+        }
+        let quick = quick_add::parse(self.name.value(), Local::now().naive_local());
+        quick.name.trim().is_empty()
+    }
+
+    /// Splits a name like `Fix login bug #backend !high due:fri` into the name, tags,
+    /// priority and due date it carries. A due date typed into the Due field and a
+    /// priority picked with `cycle_priority` win over the ones in the name.
+    fn apply_quick_add(&mut self) {
+        let quick = quick_add::parse(self.name.value(), Local::now().naive_local());
+        self.name = Input::from(quick.name);
+        if !quick.tags.is_empty() {
+            let mut tags = tags::parse(self.tags.value());
+            for tag in quick.tags {
+                if !tags.contains(&tag) {
+                    tags.push(tag);
+                }
+            }
+            self.tags = Input::from(tags::format(&tags));
+        }
+        if let (Some(priority), Priority::None) = (quick.priority, self.metadata.priority) {
+            self.metadata.priority = priority;
+        }
+        if self.metadata.due_date.is_none() {
+            self.metadata.due_date = quick.due;
+        }
+    }
+
+    /// The name with the quick-add tokens highlighted as they are recognised.
+    fn name_spans(&self) -> Spans<'_> {
+        let value = self.name.value();
+        // names of existing todos are taken as they are
+        if self.is_editing_existing {
+            return Spans::from(value);
+        }
+        let tokens = quick_add::tokenize(value, Local::now().naive_local());
+        Spans::from(
+            tokens
+                .into_iter()
+                .map(|(s, token)| {
+                    let style = match token {
+                        Token::Text => Style::default(),
+                        Token::Tag => Style::default()
+                            .fg(self.theme.tag_fg)
+                            .bg(self.theme.tag_color(&s[1..].to_lowercase())),
+                        Token::Priority => s[1..]
+                            .parse()
+                            .ok()
+                            .and_then(|p| self.theme.priority_style(p))
+                            .map(|p| Style::default().fg(p.color).add_modifier(Modifier::BOLD))
+                            .unwrap_or_default(),
+                        Token::Due => Style::default().fg(self.theme.due_soon),
+                    };
+                    Span::styled(s, style)
+                })
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    fn focused_input(&mut self) -> &mut Input {
+        match self.focused_field {
+            InputField::Name => &mut self.name,
+            InputField::Due => &mut self.due,
+            InputField::Recurrence => &mut self.recurrence,
+            InputField::Tags => &mut self.tags,
+        }
+    }
+
+    /// Starts a new todo with the given tags, used to keep it visible under a tag filter.
+    pub fn prefill_tags(&mut self, tags: &[String]) {
+        self.tags = Input::from(tags::format(tags));
     }
 
     pub fn clear(&mut self) {
         self.name = Input::default();
+        self.due = Input::default();
+        self.recurrence = Input::default();
+        self.tags = Input::default();
+        self.focused_field = InputField::Name;
         self.description.clear();
         self.metadata = TodoMetadata::default();
+        self.subtasks.clear();
         self.is_editing_existing = false;
+        self.todo_id = 0;
     }
 }
 
@@ -95,22 +266,88 @@ impl Component for TodoInputComponent {
 
         let chunks = Layout::default()
             .direction(Direction::Vertical)
-            .constraints([Constraint::Length(3), Constraint::Max(10)].as_ref())
+            .constraints(
+                [
+                    Constraint::Length(3),
+                    Constraint::Length(3),
+                    Constraint::Length(3),
+                    Constraint::Length(3),
+                    Constraint::Max(10),
+                ]
+                .as_ref(),
+            )
             .split(rect);
 
-        let name_input = &self.name;
+        let border_style = |field: InputField| {
+            if field == self.focused_field {
+                Style::default().fg(self.theme.move_mode_border)
+            } else {
+                Style::default().fg(self.theme.border)
+            }
+        };
+
         let width = chunks[0].width.max(3) - 3;
+
+        let name_title = match self.metadata.priority {
+            Priority::None => "Name".to_string(),
+            p => format!("Name ({p} priority)"),
+        };
+        let name_input = &self.name;
         let scroll = (name_input.cursor() as u16).max(width) - width;
-        let name_input = Paragraph::new(name_input.value())
+        let name_input = Paragraph::new(self.name_spans()).scroll((0, scroll)).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(border_style(InputField::Name))
+                .title(name_title),
+        );
+
+        // previews what the typed date is understood as
+        let (due_title, due_border) = match self.parse_due() {
+            Ok(None) => ("Due".to_string(), border_style(InputField::Due)),
+            Ok(Some(dt)) => (
+                format!(
+                    "Due: {} ({})",
+                    dt.format(PREVIEW_FORMAT),
+                    due::relative_label(dt, Local::now().naive_local())
+                ),
+                border_style(InputField::Due),
+            ),
+            Err(_) => (
+                "Due (not a date)".to_string(),
+                Style::default().fg(self.theme.due_overdue),
+            ),
+        };
+        let due_input = &self.due;
+        let scroll = (due_input.cursor() as u16).max(width) - width;
+        let due_input = Paragraph::new(due_input.value()).scroll((0, scroll)).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(due_border)
+                .title(due_title),
+        );
+
+        let recurrence_input = &self.recurrence;
+        let scroll = (recurrence_input.cursor() as u16).max(width) - width;
+        let recurrence_input = Paragraph::new(recurrence_input.value())
             .scroll((0, scroll))
             .block(
                 Block::default()
                     .borders(Borders::ALL)
-                    .border_style(Style::default().fg(self.theme.border))
-                    .title("Name"),
+                    .border_style(border_style(InputField::Recurrence))
+                    .title("Recurrence"),
+            );
+
+        let tags_input = &self.tags;
+        let scroll = (tags_input.cursor() as u16).max(width) - width;
+        let tags_input = Paragraph::new(tags_input.value())
+            .scroll((0, scroll))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(border_style(InputField::Tags))
+                    .title("Tags"),
             );
 
-        let width = chunks[1].width.max(3) - 3;
         let desc_input = Paragraph::new(&*self.description)
             .wrap(Wrap { trim: true })
             .block(
@@ -122,13 +359,23 @@ impl Component for TodoInputComponent {
 
         f.render_widget(Clear, chunks[0]);
         f.render_widget(Clear, chunks[1]);
+        f.render_widget(Clear, chunks[2]);
+        f.render_widget(Clear, chunks[3]);
+        f.render_widget(Clear, chunks[4]);
 
         f.render_widget(name_input, chunks[0]);
-        f.render_widget(desc_input, chunks[1]);
-        f.set_cursor(
-            chunks[0].x + (self.name.cursor() as u16).min(width) + 1,
-            chunks[0].y + 1,
-        );
+        f.render_widget(due_input, chunks[1]);
+        f.render_widget(recurrence_input, chunks[2]);
+        f.render_widget(tags_input, chunks[3]);
+        f.render_widget(desc_input, chunks[4]);
+
+        let (cursor, chunk) = match self.focused_field {
+            InputField::Name => (self.name.cursor(), chunks[0]),
+            InputField::Due => (self.due.cursor(), chunks[1]),
+            InputField::Recurrence => (self.recurrence.cursor(), chunks[2]),
+            InputField::Tags => (self.tags.cursor(), chunks[3]),
+        };
+        f.set_cursor(chunk.x + (cursor as u16).min(width) + 1, chunk.y + 1);
     }
 
     fn handle_input(&mut self, key: KeyEvent) -> Result<Self::Message, Box<dyn Error>> {
@@ -137,10 +384,29 @@ impl Component for TodoInputComponent {
             self.clear();
             return Ok(AppMessage::InputState(AppState::Normal));
         } else if key_match(&key, &self.keys.submit) {
+            match self.parse_due() {
+                Ok(due) => self.metadata.due_date = due,
+                Err(e) => {
+                    self.flash_tx
+                        .send(FlashMsg::err(format!("Invalid due date: {e}")))?;
+                    return Ok(AppMessage::NoAction);
+                }
+            }
+            if let Err(e) = self.apply_recurrence() {
+                self.flash_tx
+                    .send(FlashMsg::err(format!("Invalid recurrence: {e}")))?;
+                return Ok(AppMessage::NoAction);
+            }
+            if self.missing_name() {
+                self.flash_tx.send(FlashMsg::err("Missing todo name"))?;
+                return Ok(AppMessage::NoAction);
+            }
+            if !self.is_editing_existing {
+                self.apply_quick_add();
+            }
             if self.is_editing_existing {
                 return Ok(AppMessage::UpdateList(ListAction::Replace(
                     self.clone().into(),
-                    self.todo_index,
                 )));
             } else {
                 return Ok(AppMessage::UpdateList(ListAction::Add(self.clone().into())));
@@ -150,11 +416,21 @@ impl Component for TodoInputComponent {
             self.description = desc;
             return Ok(AppMessage::ReInitTerminal);
         } else if key_match(&key, &self.keys.mark_recurring) {
-            self.metadata.recurring = !self.metadata.recurring;
+            self.focused_field = match self.focused_field {
+                InputField::Recurrence => InputField::Name,
+                _ => InputField::Recurrence,
+            };
+        } else if key_match(&key, &self.keys.cycle_priority) {
+            self.metadata.priority = self.metadata.priority.cycle();
+        } else if key_match(&key, &self.keys.alt_move_down) {
+            self.focused_field = self.focused_field.next();
+        } else if key_match(&key, &self.keys.alt_move_up) {
+            self.focused_field = self.focused_field.prev();
         } else if key_match(&key, &self.keys.open_calendar) {
             return Ok(AppMessage::InputState(AppState::DueDate));
         } else {
-            input_backend::to_input_request(Event::Key(key)).and_then(|r| self.name.handle(r));
+            input_backend::to_input_request(Event::Key(key))
+                .and_then(|r| self.focused_input().handle(r));
         }
         Ok(AppMessage::NoAction)
     }
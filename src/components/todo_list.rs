@@ -1,62 +1,132 @@
+use super::history::{History, ListOp};
 use super::notification::FlashMsg;
 use crate::app::{AppMessage, AppState};
+use crate::due::{self, DueStatus};
+use crate::git;
 use crate::keys::keymap::SharedKeyList;
+use crate::merge::{self, Prefer};
+use crate::priority::Priority;
+use crate::recurrence::Recurrence;
+use crate::remind::LeadTime;
+use crate::settings::settings::SharedSettings;
+use crate::store::{self, ListLock, TodoListSerde};
+use crate::subtask::{self, Subtask, SubtaskPath};
+use crate::sync::{self, Conflict, Resolution};
+use crate::tags;
 use crate::theme::theme::SharedTheme;
+use crate::view::{GroupMode, ListView};
 use crate::widgets::hint_bar::{BarType, HintBar};
 use crate::widgets::scrollbar::Scrollbar;
 use crate::widgets::stateful_paragraph::paragraph::ScrollSelection;
 use crate::widgets::stateful_paragraph::{ParagraphState, ScrollPos, StatefulParagraph};
+use crate::EVENT_TIMEOUT;
 use anyhow::Result;
-use chrono::{DateTime, Local, NaiveDateTime};
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveDateTime};
 use crossterm::event::KeyEvent;
 use kanal::Sender;
 use ratatui::backend::Backend;
-use ratatui::layout::{Constraint, Direction, Layout};
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
 use ratatui::style::{Modifier, Style};
 use ratatui::text::{Span, Spans};
-use ratatui::widgets::{Block, Borders, List, ListItem, Paragraph};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph};
 use ratatui::Frame;
 use serde::{Deserialize, Serialize};
 use std::cell::Cell;
+use std::collections::HashSet;
+use std::env;
 use std::error::Error;
-use std::io;
+use std::path::Path;
+use std::time::{Instant, SystemTime};
 use tui_utils::blocks::Dim;
 use tui_utils::component::Component;
 use tui_utils::keys::key_match;
 use tui_utils::state::{Boundary, BoundedState, StateWrap};
 use tui_utils::LIST_HIGHLIGHT_SYMBOL;
 
-static TIME_FORMAT: &str = "%D %-I:%M %P";
+pub static TIME_FORMAT: &str = "%D %-I:%M %P";
+
+/// Persistent identifier of a todo. `0` means that no id has been assigned yet.
+pub type TodoId = u64;
 
 pub enum ListAction {
-    Replace(Todo, usize),
+    Replace(Todo),
     Add(Todo),
+    AddSubtask(String),
 }
 
-#[derive(Serialize, Deserialize, Default, Clone, Debug)]
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
 pub struct Todo {
+    #[serde(default)]
+    pub id: TodoId,
     #[serde(default)]
     pub name: String,
     pub description: String,
     pub metadata: TodoMetadata,
+    #[serde(default)]
+    pub subtasks: Vec<Subtask>,
+    #[serde(default)]
+    pub tags: Vec<String>,
 }
 
 impl Todo {
     pub fn toggle_finished(&mut self) {
         self.metadata.finished = !self.metadata.finished;
     }
+
+    /// Completes the current occurrence of a recurring todo by rolling its due date
+    /// forward to the next occurrence in the future, which is returned.
+    pub fn complete_occurrence(&mut self) -> Option<NaiveDateTime> {
+        let rule = self.metadata.recurrence.as_ref()?;
+        let now = Local::now();
+        let from = self
+            .metadata
+            .due_date
+            .map(|dd| rule.next_after(dd))
+            .unwrap_or_else(|| now.naive_local());
+        let next = rule.next_occurrence(from, now.naive_local());
+
+        self.metadata.due_date = Some(next);
+        self.metadata.last_completed = Some(now);
+        subtask::reset(&mut self.subtasks);
+        Some(next)
+    }
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct TodoMetadata {
     pub added_at: DateTime<Local>,
     pub edited_at: Option<DateTime<Local>>,
     pub due_date: Option<NaiveDateTime>,
-    pub recurring: bool,
+    #[serde(default)]
+    pub priority: Priority,
+    #[serde(default)]
+    pub recurrence: Option<Recurrence>,
+    /// Overrides the default reminder lead time from the settings.
+    #[serde(default)]
+    pub remind_before: Option<LeadTime>,
+    #[serde(default)]
+    pub last_completed: Option<DateTime<Local>>,
     pub finished: bool,
+    // files written before recurrence rules existed only stored a flag
+    #[serde(default, rename = "recurring", skip_serializing)]
+    pub(crate) legacy_recurring: bool,
 }
 
 impl TodoMetadata {
+    #[inline(always)]
+    pub fn is_recurring(&self) -> bool {
+        self.recurrence.is_some()
+    }
+
+    /// The upcoming occurrence of a recurring todo, which is its due date
+    /// unless that has already passed.
+    pub fn next_occurrence(&self) -> Option<NaiveDateTime> {
+        let rule = self.recurrence.as_ref()?;
+        let now = Local::now().naive_local();
+        let from = self.due_date.unwrap_or(now);
+        Some(rule.next_occurrence(from, now))
+    }
+
     pub fn to_formatted(&self) -> Vec<(&'static str, String)> {
         #[inline(always)]
         fn yes_no(b: bool) -> &'static str {
@@ -67,7 +137,7 @@ impl TodoMetadata {
             }
         }
 
-        let mut c = Vec::with_capacity(5);
+        let mut c = Vec::with_capacity(9);
         c.push(("Added: ", self.added_at.format(TIME_FORMAT).to_string()));
 
         let edited_at = if let Some(ea) = self.edited_at {
@@ -84,8 +154,22 @@ impl TodoMetadata {
 
         c.push(("Edited: ", edited_at));
         c.push(("Due date: ", due_date));
-        c.push(("Recurring: ", yes_no(self.recurring).into()));
-        c.push(("Finished: ", yes_no(self.finished).into()));
+        c.push(("Priority: ", self.priority.to_string()));
+        if let Some(lead) = self.remind_before {
+            c.push(("Reminder: ", format!("{lead} before")));
+        }
+        if let Some(rule) = &self.recurrence {
+            c.push(("Recurring: ", rule.to_string()));
+            if let Some(next) = self.next_occurrence() {
+                c.push(("Next: ", next.format(TIME_FORMAT).to_string()));
+            }
+            if let Some(lc) = self.last_completed {
+                c.push(("Last done: ", lc.format(TIME_FORMAT).to_string()));
+            }
+        } else {
+            c.push(("Recurring: ", "no".into()));
+            c.push(("Finished: ", yes_no(self.finished).into()));
+        }
         c
     }
 }
@@ -96,39 +180,94 @@ impl Default for TodoMetadata {
             added_at: Local::now(),
             edited_at: None,
             due_date: None,
-            recurring: false,
+            priority: Priority::None,
+            recurrence: None,
+            remind_before: None,
+            last_completed: None,
             finished: false,
+            legacy_recurring: false,
         }
     }
 }
 
-#[derive(Deserialize, Serialize, Debug, Default)]
-struct TodoListSerde {
-    todos: Vec<Todo>,
-}
-
 impl From<&TodoListComponent> for TodoListSerde {
     fn from(other: &TodoListComponent) -> Self {
         Self {
+            version: store::SCHEMA_VERSION,
+            next_id: other.next_id,
             todos: other.todos.clone(),
         }
     }
 }
 
+/// Shortens paths in the home directory to start with `~`.
+fn display_path(path: &Path) -> String {
+    match env::var_os("HOME").and_then(|home| path.strip_prefix(home).ok().map(Path::to_path_buf)) {
+        Some(rest) => format!("~/{}", rest.display()),
+        None => path.display().to_string(),
+    }
+}
+
+/// A line of the list, showing either a todo or one of its subtasks.
+struct Row {
+    todo: usize,
+    // empty for the todo itself
+    path: SubtaskPath,
+}
+
 pub struct TodoListComponent {
+    pub list_name: String,
     pub list_state: BoundedState,
     paragraph_state: Cell<ParagraphState>,
     pub todos: Vec<Todo>,
+    rows: Vec<Row>,
+    // todos whose subtasks are shown
+    expanded: HashSet<TodoId>,
+    // only todos carrying all of these tags are shown
+    tag_filter: Vec<String>,
+    view: ListView,
+    // section headers as (index of the first row in the section, title)
+    sections: Vec<(usize, &'static str)>,
+    // the rendered list, which also contains the section headers
+    display_state: ListState,
+    // the time due labels and sections are relative to, refreshed by `tick`
+    now: NaiveDateTime,
+    last_tick: Instant,
+    next_id: TodoId,
     keys: SharedKeyList,
     theme: SharedTheme,
+    settings: SharedSettings,
     hintbars: HintBars,
     move_mode: bool,
+    // order of the todos when move mode was entered
+    order_before_move: Vec<TodoId>,
+    history: History,
+    // the todos as last read from or written to disk, which changes made elsewhere are merged against
+    on_disk: Vec<Todo>,
+    // when the list file was last written back then
+    disk_stamp: Option<SystemTime>,
+    disk_conflict: Option<DiskConflict>,
     flash_tx: Sender<FlashMsg>,
 }
 
+/// Changes made to the list file elsewhere that conflict with local ones.
+pub struct DiskConflict {
+    disk: TodoListSerde,
+    stamp: Option<SystemTime>,
+    /// Names of the todos changed on both sides.
+    pub names: Vec<String>,
+}
+
+/// How to settle a `DiskConflict`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DiskChoice {
+    Merge(Prefer),
+    Reload,
+}
+
 pub struct HintBars {
     selected: usize,
-    items: [HintBar; 5],
+    items: [HintBar; 12],
 }
 
 impl HintBars {
@@ -140,16 +279,51 @@ impl HintBars {
                 HintBar::edit_mode(keys.clone(), theme.clone()),
                 HintBar::move_mode(keys.clone(), theme.clone()),
                 HintBar::find_mode(keys.clone(), theme.clone()),
-                HintBar::due_date_mode(keys, theme),
+                HintBar::due_date_mode(keys.clone(), theme.clone()),
+                HintBar::lists_mode(keys.clone(), theme.clone()),
+                HintBar::subtask_mode(keys.clone(), theme.clone()),
+                HintBar::filter_mode(keys.clone(), theme.clone()),
+                HintBar::conflict_mode(keys.clone(), theme.clone()),
+                HintBar::disk_changed_mode(keys.clone(), theme.clone()),
+                HintBar::calendar_mode(keys.clone(), theme.clone()),
+                HintBar::agenda_mode(keys, theme),
             ],
         }
     }
 }
 
 impl TodoListComponent {
-    pub fn load(keys: SharedKeyList, theme: SharedTheme, flash_tx: Sender<FlashMsg>) -> Self {
-        let todo_data: TodoListSerde = confy::load("tood", Some("todos")).unwrap();
+    pub fn load(
+        list_name: String,
+        keys: SharedKeyList,
+        theme: SharedTheme,
+        settings: SharedSettings,
+        flash_tx: Sender<FlashMsg>,
+    ) -> Self {
+        let todo_data = match TodoListSerde::load_or_recover(&list_name) {
+            Ok((todo_data, recovered)) => {
+                if let Some(msg) = recovered {
+                    flash_tx.send(FlashMsg::warn(msg)).unwrap();
+                }
+                todo_data
+            }
+            Err(e) => {
+                flash_tx
+                    .send(FlashMsg::err(format!(
+                        "Failed to load list {list_name}: {e}"
+                    )))
+                    .unwrap();
+                TodoListSerde::default()
+            }
+        };
+        let view = ListView::load().unwrap_or_else(|e| {
+            flash_tx
+                .send(FlashMsg::err(format!("Failed to load list view: {e}")))
+                .unwrap();
+            ListView::default()
+        });
 
+        let disk_stamp = store::modified(&list_name).ok().flatten();
         let b = Boundary::from(&todo_data.todos);
         let mut state = BoundedState::new(b, StateWrap::Enable);
 
@@ -158,15 +332,45 @@ impl TodoListComponent {
             state.first();
         }
 
-        Self {
+        let mut list = Self {
+            list_name,
             list_state: state,
             paragraph_state: Cell::new(ParagraphState::default()),
+            on_disk: todo_data.todos.clone(),
             todos: todo_data.todos,
+            rows: Vec::new(),
+            expanded: HashSet::new(),
+            tag_filter: Vec::new(),
+            view,
+            sections: Vec::new(),
+            display_state: ListState::default(),
+            now: Local::now().naive_local(),
+            last_tick: Instant::now(),
+            next_id: todo_data.next_id,
             keys: keys.clone(),
             theme: theme.clone(),
+            settings,
             hintbars: HintBars::new(keys, theme),
             move_mode: false,
+            order_before_move: Vec::new(),
+            history: History::default(),
+            disk_stamp,
+            disk_conflict: None,
             flash_tx,
+        };
+        list.rebuild_rows(None);
+        list.warn_if_untracked();
+        list
+    }
+
+    fn warn_if_untracked(&self) {
+        match git::untracked(&self.settings, &self.list_name) {
+            Ok(Some(reason)) => self.flash_tx.send(FlashMsg::warn(reason)).unwrap(),
+            Ok(None) => {}
+            Err(e) => self
+                .flash_tx
+                .send(FlashMsg::err(format!("Failed to check git history: {e}")))
+                .unwrap(),
         }
     }
 
@@ -174,22 +378,453 @@ impl TodoListComponent {
         &self.todos
     }
 
-    pub fn add_todo(&mut self, t: Todo) -> Result<()> {
+    pub fn index_of(&self, id: TodoId) -> Option<usize> {
+        self.todos.iter().position(|t| t.id == id)
+    }
+
+    fn selected_row(&self) -> Option<&Row> {
+        self.list_state.inner().selected().map(|s| &self.rows[s])
+    }
+
+    /// The todo id and subtask path of the selected row, which stay valid across a rebuild.
+    fn selected_key(&self) -> Option<(TodoId, SubtaskPath)> {
+        self.selected_row()
+            .map(|r| (self.todos[r.todo].id, r.path.clone()))
+    }
+
+    fn row_index(&self, id: TodoId, path: &[usize]) -> Option<usize> {
+        self.rows
+            .iter()
+            .position(|r| self.todos[r.todo].id == id && r.path == path)
+    }
+
+    /// Recomputes the visible rows after the todos changed. Selects the row of `select`,
+    /// falling back to its todo and then to the previously selected position.
+    fn rebuild_rows(&mut self, select: Option<(TodoId, SubtaskPath)>) {
+        let previous = self.list_state.inner().selected();
+
+        let mut order: Vec<usize> = (0..self.todos.len())
+            .filter(|i| tags::matches(&self.todos[*i].tags, &self.tag_filter))
+            .collect();
+        // stable, so todos that compare equal keep their manual order
+        self.view.arrange(&self.todos, &mut order);
+
+        let now = self.now;
+        self.rows.clear();
+        self.sections.clear();
+        for i in order {
+            let t = &self.todos[i];
+            if self.view.group != GroupMode::None {
+                let title = self.view.group.title(self.view.group.group_of(t, now));
+                if self.sections.last().map(|(_, last)| *last) != Some(title) {
+                    self.sections.push((self.rows.len(), title));
+                }
+            }
+            self.rows.push(Row {
+                todo: i,
+                path: Vec::new(),
+            });
+            if self.expanded.contains(&t.id) {
+                let mut paths = Vec::new();
+                subtask::flatten(&t.subtasks, &[], &mut paths);
+                self.rows
+                    .extend(paths.into_iter().map(|path| Row { todo: i, path }));
+            }
+        }
+        self.list_state.update_boundary_from_vec(&self.rows);
+
+        if self.rows.is_empty() {
+            self.list_state.deselect();
+            return;
+        }
+        let i = select
+            .and_then(|(id, path)| {
+                self.row_index(id, &path)
+                    .or_else(|| self.row_index(id, &[]))
+            })
+            .unwrap_or_else(|| previous.unwrap_or(0).min(self.rows.len() - 1));
+        self.list_state.select(i).unwrap();
+    }
+
+    /// Moves the list's notion of the current time forward, at most once per `EVENT_TIMEOUT`.
+    pub fn tick(&mut self) {
+        if self.last_tick.elapsed() < EVENT_TIMEOUT {
+            return;
+        }
+        self.last_tick = Instant::now();
+        self.now = Local::now().naive_local();
+        // todos move between the due date sections as time passes
+        if self.view.group == GroupMode::DueDate {
+            self.rebuild_rows(self.selected_key());
+        }
+        self.check_disk();
+    }
+
+    /// Picks up changes that other instances or the cli made to the list file.
+    fn check_disk(&mut self) {
+        if self.disk_conflict.is_some() || self.move_mode {
+            return;
+        }
+        let stamp = match store::modified(&self.list_name) {
+            Ok(Some(stamp)) => Some(stamp),
+            // a deleted file is recreated by the next save
+            _ => return,
+        };
+        if stamp == self.disk_stamp {
+            return;
+        }
+        if self.todos != self.on_disk {
+            // an earlier save failed, merge its changes in now
+            self.save_to_disk();
+            return;
+        }
+        // the file may be in the middle of being written by an editor, try again later then
+        let Ok(disk) = TodoListSerde::read(&self.list_name) else {
+            return;
+        };
+        self.replace_all(disk);
+        self.on_disk = self.todos.clone();
+        self.disk_stamp = stamp;
+        self.flash_tx
+            .send(FlashMsg::info(
+                "Reloaded the list, it was changed elsewhere",
+            ))
+            .unwrap();
+    }
+
+    pub fn disk_conflict(&self) -> Option<&DiskConflict> {
+        self.disk_conflict.as_ref()
+    }
+
+    pub fn resolve_disk_conflict(&mut self, choice: DiskChoice) {
+        let Some(conflict) = self.disk_conflict.take() else {
+            return;
+        };
+        let base = std::mem::replace(&mut self.on_disk, conflict.disk.todos.clone());
+        self.disk_stamp = conflict.stamp;
+        match choice {
+            DiskChoice::Reload => self.replace_all(conflict.disk),
+            DiskChoice::Merge(prefer) => {
+                let local = TodoListSerde::from(&*self);
+                let merged = merge::merge(&base, &local, &conflict.disk, prefer);
+                self.replace_all(merged.data);
+                self.save("Merge changes made elsewhere");
+            }
+        }
+    }
+
+    /// Takes over a new version of the whole list, keeping the selection on the same todo.
+    fn replace_all(&mut self, data: TodoListSerde) {
+        let select = self.selected_key();
+        self.todos = data.todos;
+        self.next_id = data.next_id;
+        // undoing could revert changes made elsewhere
+        self.history = History::default();
+        self.rebuild_rows(select);
+    }
+
+    pub fn cycle_sort_mode(&mut self) -> Result<()> {
+        self.view.sort = self.view.sort.next();
+        self.update_view(format!("Sorting by {}", self.view.sort))
+    }
+
+    pub fn cycle_group_mode(&mut self) -> Result<()> {
+        self.view.group = self.view.group.next();
+        let msg = match self.view.group {
+            GroupMode::None => "Not grouping".to_string(),
+            group => format!("Grouping by {group}"),
+        };
+        self.update_view(msg)
+    }
+
+    fn update_view(&mut self, msg: String) -> Result<()> {
+        self.rebuild_rows(self.selected_key());
+        let msg = match self.view.store() {
+            Ok(()) => FlashMsg::info(msg),
+            Err(e) => FlashMsg::err(format!("Failed to save list view: {e}")),
+        };
+        self.flash_tx.send(msg)?;
+        Ok(())
+    }
+
+    fn change_priority(&mut self, change: fn(Priority) -> Priority) -> Result<()> {
+        let Some(s) = self.selected_row().map(|r| r.todo) else {
+            self.report_no_selection();
+            return Ok(());
+        };
+        let priority = change(self.todos[s].metadata.priority);
+        if priority == self.todos[s].metadata.priority {
+            return Ok(());
+        }
+
+        let before = self.todos[s].clone();
+        self.todos[s].metadata.priority = priority;
+        self.history.record(ListOp::Replace {
+            before,
+            after: self.todos[s].clone(),
+        });
+        self.rebuild_rows(self.selected_key());
+        self.save(format!("Set priority {priority}: {}", self.todos[s].name));
+        self.flash_tx
+            .send(FlashMsg::info(format!("Priority: {priority}")))?;
+        Ok(())
+    }
+
+    pub fn tag_filter(&self) -> &[String] {
+        &self.tag_filter
+    }
+
+    pub fn set_tag_filter(&mut self, mut filter: Vec<String>) {
+        filter.sort();
+        self.tag_filter = filter;
+        self.rebuild_rows(self.selected_key());
+    }
+
+    /// Every tag used in the list, sorted by name.
+    pub fn all_tags(&self) -> Vec<String> {
+        let mut all: Vec<String> = self
+            .todos
+            .iter()
+            .flat_map(|t| t.tags.iter().cloned())
+            .collect();
+        all.sort();
+        all.dedup();
+        all
+    }
+
+    pub fn add_todo(&mut self, mut t: Todo) -> Result<()> {
+        t.id = self.next_id;
+        self.next_id += 1;
+        self.history.record(ListOp::Add {
+            todo: t.clone(),
+            index: self.todos.len(),
+        });
+        let (id, change) = (t.id, format!("Add: {}", t.name));
         self.todos.push(t);
-        self.list_state
-            .update_upper_and_select(self.todos.len() - 1);
-        self.save_to_disk()?;
+        self.rebuild_rows(Some((id, Vec::new())));
+        self.save(change);
+        Ok(())
+    }
+
+    pub fn replace(&mut self, t: Todo) -> Result<()> {
+        if let Some(i) = self.index_of(t.id) {
+            let change = format!("Edit: {}", t.name);
+            let before = std::mem::replace(&mut self.todos[i], t.clone());
+            self.history.record(ListOp::Replace { before, after: t });
+            self.rebuild_rows(self.selected_key());
+            self.save(change);
+        } else {
+            self.flash_tx.send(FlashMsg::err("Todo no longer exists"))?;
+        }
+        Ok(())
+    }
+
+    /// Moves a todo to another day, keeping the time it is due at.
+    pub fn reschedule(&mut self, id: TodoId, date: NaiveDate) -> Result<()> {
+        let Some(i) = self.index_of(id) else {
+            self.flash_tx.send(FlashMsg::err("Todo no longer exists"))?;
+            return Ok(());
+        };
+        let Some(due) = self.todos[i].metadata.due_date else {
+            return Ok(());
+        };
+        if due.date() == date {
+            return Ok(());
+        }
+
+        let before = self.todos[i].clone();
+        self.todos[i].metadata.due_date = Some(date.and_time(due.time()));
+        self.history.record(ListOp::Replace {
+            before,
+            after: self.todos[i].clone(),
+        });
+        self.rebuild_rows(self.selected_key());
+        self.save(format!("Reschedule to {date}: {}", self.todos[i].name));
+        self.flash_tx.send(FlashMsg::info(format!(
+            "Moved to {}",
+            date.format("%a %b %-d %Y")
+        )))?;
+        Ok(())
+    }
+
+    /// Writes the list to disk, reporting failures as a flash message.
+    fn save_to_disk(&mut self) -> bool {
+        match self.write_merged() {
+            Ok(saved) => saved,
+            Err(e) => {
+                self.flash_tx
+                    .send(FlashMsg::err(format!("Failed to save list: {e}")))
+                    .unwrap();
+                false
+            }
+        }
+    }
+
+    /// Writes the list, first merging in changes made to the file elsewhere since it was
+    /// last read. Nothing is written while such changes conflict with the local ones.
+    fn write_merged(&mut self) -> Result<bool> {
+        if self.disk_conflict.is_some() {
+            return Ok(false);
+        }
+        let _lock = ListLock::acquire(&self.list_name)?;
+        let stamp = store::modified(&self.list_name)?;
+        if stamp.is_some() && stamp != self.disk_stamp {
+            let disk = TodoListSerde::read(&self.list_name)?;
+            let local = TodoListSerde::from(&*self);
+            let merged = merge::merge(&self.on_disk, &local, &disk, Prefer::Local);
+            if !merged.conflicts.is_empty() {
+                let names = merged
+                    .conflicts
+                    .iter()
+                    .filter_map(|id| {
+                        let in_list = |todos: &[Todo]| todos.iter().find(|t| t.id == *id).cloned();
+                        in_list(&local.todos).or_else(|| in_list(&disk.todos))
+                    })
+                    .map(|t| t.name)
+                    .collect();
+                self.disk_conflict = Some(DiskConflict { disk, stamp, names });
+                return Ok(false);
+            }
+            self.replace_all(merged.data);
+            self.flash_tx
+                .send(FlashMsg::info("Merged changes made to the list elsewhere"))?;
+        }
+        TodoListSerde::from(&*self).store(&self.list_name)?;
+        self.on_disk = self.todos.clone();
+        self.disk_stamp = store::modified(&self.list_name)?;
+        Ok(true)
+    }
+
+    /// Saves the list and, with git history turned on, commits the change.
+    fn save(&mut self, change: impl AsRef<str>) {
+        if self.save_to_disk() {
+            self.commit(change.as_ref());
+        }
+    }
+
+    fn commit(&self, change: &str) {
+        if let Err(e) = git::record(&self.settings, change) {
+            self.flash_tx
+                .send(FlashMsg::err(format!("Failed to commit change: {e}")))
+                .unwrap();
+        }
+    }
+
+    /// Loads another list in place of the current one, returning whether that worked.
+    pub fn switch_list(&mut self, list_name: String) -> Result<bool> {
+        let todo_data = match TodoListSerde::load_or_recover(&list_name) {
+            Ok((todo_data, recovered)) => {
+                if let Some(msg) = recovered {
+                    self.flash_tx.send(FlashMsg::warn(msg))?;
+                }
+                todo_data
+            }
+            Err(e) => {
+                self.flash_tx.send(FlashMsg::err(format!(
+                    "Failed to load list {list_name}: {e}"
+                )))?;
+                return Ok(false);
+            }
+        };
+        self.disk_stamp = store::modified(&list_name)?;
+        self.on_disk = todo_data.todos.clone();
+        self.disk_conflict = None;
+        self.todos = todo_data.todos;
+        self.next_id = todo_data.next_id;
+        self.list_name = list_name;
+        // the history only makes sense for the list it was recorded on
+        self.history = History::default();
+        self.expanded.clear();
+        self.tag_filter.clear();
+
+        self.list_state.deselect();
+        self.rebuild_rows(None);
+        self.warn_if_untracked();
+        Ok(true)
+    }
+
+    /// Moves the selected todo to the end of another list.
+    pub fn move_selected_to(&mut self, list_name: &str) -> Result<()> {
+        let Some(selected) = self.selected_row().map(|r| r.todo) else {
+            self.report_no_selection();
+            return Ok(());
+        };
+        if list_name == self.list_name {
+            self.flash_tx
+                .send(FlashMsg::warn("Todo is already in this list"))?;
+            return Ok(());
+        }
+
+        // taken out here first, so a failed save can't leave the todo in both lists
+        let todo = self.todos.remove(selected);
+        self.rebuild_rows(None);
+        if !self.save_to_disk() {
+            self.put_back(selected, todo);
+            return Ok(());
+        }
+
+        let moved = ListLock::acquire(list_name).and_then(|_lock| {
+            let mut target = TodoListSerde::load(list_name)?;
+            target.push(todo.clone());
+            target.store(list_name)
+        });
+        if let Err(e) = moved {
+            self.flash_tx
+                .send(FlashMsg::err(format!("Failed to move todo: {e}")))?;
+            self.put_back(selected, todo);
+            self.save_to_disk();
+            return Ok(());
+        }
+
+        // the todo now lives in another list, so there is nothing here to undo
+        self.commit(&format!("Move to {list_name}: {}", todo.name));
+        self.flash_tx.send(FlashMsg::info(format!(
+            "Moved '{}' to {list_name}, this can't be undone",
+            todo.name
+        )))?;
         Ok(())
     }
 
-    pub fn replace(&mut self, t: Todo, i: usize) -> Result<()> {
-        let _ = std::mem::replace(&mut self.todos[i], t);
-        self.save_to_disk()?;
+    fn put_back(&mut self, index: usize, todo: Todo) {
+        let id = todo.id;
+        self.todos.insert(index.min(self.todos.len()), todo);
+        self.rebuild_rows(Some((id, Vec::new())));
+    }
+
+    /// Syncs the list with its CalDAV collection and returns the conflicts left to resolve.
+    pub fn sync(&mut self) -> Result<Vec<Conflict>> {
+        let mut data = TodoListSerde::from(&*self);
+        match sync::sync(&self.list_name, &mut data) {
+            Ok(report) => {
+                self.apply_synced(data, &report.describe())?;
+                self.flash_tx.send(FlashMsg::info(report.describe()))?;
+                Ok(report.conflicts)
+            }
+            Err(e) => {
+                self.flash_tx
+                    .send(FlashMsg::err(format!("Failed to sync: {e}")))?;
+                Ok(Vec::new())
+            }
+        }
+    }
+
+    pub fn resolve_conflict(&mut self, conflict: &Conflict, resolution: Resolution) -> Result<()> {
+        let mut data = TodoListSerde::from(&*self);
+        match sync::resolve(&self.list_name, &mut data, conflict, resolution) {
+            Ok(()) => {
+                self.apply_synced(data, &format!("Resolve conflict: {}", conflict.local.name))?
+            }
+            Err(e) => self
+                .flash_tx
+                .send(FlashMsg::err(format!("Failed to resolve conflict: {e}")))?,
+        }
         Ok(())
     }
 
-    pub fn save_to_disk(&self) -> io::Result<()> {
-        confy::store("tood", Some("todos"), TodoListSerde::from(self)).unwrap();
+    fn apply_synced(&mut self, data: TodoListSerde, change: &str) -> Result<()> {
+        // synced changes can not be undone either, the server already has them
+        self.replace_all(data);
+        self.save(change);
         Ok(())
     }
 
@@ -202,14 +837,42 @@ impl TodoListComponent {
     }
 
     pub fn remove_current(&mut self) -> Result<()> {
-        if let Some(selected) = self.list_state.inner().selected() {
-            self.todos.remove(selected);
-            self.list_state.update_boundary_from_vec(&self.todos);
-            self.save_to_disk().unwrap();
-            self.flash_tx.send(FlashMsg::info("Removed todo"))?;
+        let Some(Row {
+            todo: selected,
+            path,
+        }) = self.selected_row()
+        else {
+            self.report_no_selection();
+            return Ok(());
+        };
+        let (selected, path) = (*selected, path.clone());
+
+        if !path.is_empty() {
+            let before = self.todos[selected].clone();
+            let subtasks = &mut self.todos[selected].subtasks;
+            subtask::remove(subtasks, &path);
+            if self.settings.complete_parent_with_subtasks {
+                subtask::sync_parents(subtasks);
+            }
+            self.history.record(ListOp::Replace {
+                before,
+                after: self.todos[selected].clone(),
+            });
+            self.rebuild_rows(None);
+            self.save(format!("Remove subtask: {}", self.todos[selected].name));
+            self.flash_tx.send(FlashMsg::info("Removed subtask"))?;
             return Ok(());
         }
-        self.report_no_selection();
+
+        let todo = self.todos.remove(selected);
+        let change = format!("Remove: {}", todo.name);
+        self.history.record(ListOp::Remove {
+            todo,
+            index: selected,
+        });
+        self.rebuild_rows(None);
+        self.save(change);
+        self.flash_tx.send(FlashMsg::info("Removed todo"))?;
         Ok(())
     }
 
@@ -219,11 +882,8 @@ impl TodoListComponent {
             .unwrap();
     }
 
-    pub fn selected(&self) -> Option<(&Todo, usize)> {
-        if let Some(s) = self.list_state.inner().selected() {
-            return Some((&self.todos[s], s));
-        }
-        None
+    pub fn selected(&self) -> Option<&Todo> {
+        self.selected_row().map(|r| &self.todos[r.todo])
     }
 
     pub fn scroll_desc(&self, nav: ScrollSelection) -> bool {
@@ -266,52 +926,419 @@ impl TodoListComponent {
     }
 
     pub fn toggle_finished(&mut self) {
-        if let Some(s) = self.list_state.inner().selected() {
-            // dont toggle if the todo is recurring
-            if self.todos[s].metadata.recurring {
-                self.flash_tx
-                    .send(FlashMsg::warn("Can't mark recurring as finished"))
-                    .unwrap();
+        if let Some(Row { todo: s, path }) = self.selected_row() {
+            let (s, path) = (*s, path.clone());
+            if !path.is_empty() {
+                self.toggle_subtask(s, &path);
+                return;
+            }
+            let before = self.todos[s].clone();
+            // recurring todos move on to their next occurrence instead
+            if let Some(next) = self.todos[s].complete_occurrence() {
+                self.history.record(ListOp::Replace {
+                    before,
+                    after: self.todos[s].clone(),
+                });
+                // the new due date can move it to another place or section
+                self.rebuild_rows(Some((self.todos[s].id, Vec::new())));
+                self.save(format!("Complete: {}", self.todos[s].name));
+                let msg = format!("Next occurrence: {}", next.format(TIME_FORMAT));
+                self.flash_tx.send(FlashMsg::info(msg)).unwrap();
                 return;
             }
             self.todos[s].toggle_finished();
-            self.save_to_disk().unwrap();
-            let msg = if self.todos[s].metadata.finished {
-                "Marked todo as finished"
+            self.history.record(ListOp::Replace {
+                before,
+                after: self.todos[s].clone(),
+            });
+            let (msg, change) = if self.todos[s].metadata.finished {
+                ("Marked todo as finished", "Complete")
             } else {
-                "Marked todo as unfinished"
+                ("Marked todo as unfinished", "Reopen")
             };
+            self.rebuild_rows(Some((self.todos[s].id, Vec::new())));
+            self.save(format!("{change}: {}", self.todos[s].name));
             self.flash_tx.send(FlashMsg::info(msg)).unwrap();
         } else {
             self.report_no_selection();
         }
     }
 
-    pub fn move_todo_up(&mut self) {
-        if let Some(s) = self.list_state.inner().selected() {
-            let new_index = if s == 0 { self.todos.len() - 1 } else { s - 1 };
-            self.todos.swap(s, new_index);
-            self.list_state.select(new_index).unwrap();
-            self.save_to_disk().unwrap();
+    fn toggle_subtask(&mut self, s: usize, path: &[usize]) {
+        let before = self.todos[s].clone();
+        let complete_parent = self.settings.complete_parent_with_subtasks;
+        let todo = &mut self.todos[s];
+        let Some(finished) = subtask::toggle(&mut todo.subtasks, path, complete_parent) else {
+            return;
+        };
+
+        let (mut msg, change) = if finished {
+            ("Marked subtask as finished".to_string(), "Complete subtask")
+        } else {
+            ("Marked subtask as unfinished".to_string(), "Reopen subtask")
+        };
+        let change = format!("{change}: {}", todo.name);
+        if complete_parent && todo.subtasks.iter().all(|c| c.finished) {
+            if let Some(next) = todo.complete_occurrence() {
+                msg = format!(
+                    "All subtasks done, next occurrence: {}",
+                    next.format(TIME_FORMAT)
+                );
+            } else if !todo.metadata.finished {
+                todo.metadata.finished = true;
+                msg = "All subtasks done, marked todo as finished".into();
+            }
+        } else if complete_parent && todo.metadata.finished {
+            todo.metadata.finished = false;
         }
+
+        let id = todo.id;
+        self.history.record(ListOp::Replace {
+            before,
+            after: self.todos[s].clone(),
+        });
+        self.rebuild_rows(Some((id, path.to_vec())));
+        self.save(change);
+        self.flash_tx.send(FlashMsg::info(msg)).unwrap();
+    }
+
+    /// Adds a subtask below the selected subtask, or as the last subtask when the todo
+    /// itself is selected.
+    pub fn add_subtask(&mut self, name: String) -> Result<()> {
+        let Some(Row { todo: s, path }) = self.selected_row() else {
+            self.report_no_selection();
+            return Ok(());
+        };
+        let (s, path) = (*s, path.clone());
+
+        let before = self.todos[s].clone();
+        let todo = &mut self.todos[s];
+        let change = format!("Add subtask to {}: {name}", todo.name);
+        let new_path = subtask::insert_after(&mut todo.subtasks, &path, Subtask::new(name));
+        if self.settings.complete_parent_with_subtasks {
+            subtask::sync_parents(&mut todo.subtasks);
+        }
+
+        let id = todo.id;
+        self.expanded.insert(id);
+        self.history.record(ListOp::Replace {
+            before,
+            after: self.todos[s].clone(),
+        });
+        self.rebuild_rows(Some((id, new_path)));
+        self.save(change);
+        Ok(())
+    }
+
+    pub fn indent_subtask(&mut self) -> Result<()> {
+        self.restructure_subtask(subtask::indent)
+    }
+
+    pub fn outdent_subtask(&mut self) -> Result<()> {
+        self.restructure_subtask(subtask::outdent)
+    }
+
+    fn restructure_subtask(
+        &mut self,
+        f: fn(&mut Vec<Subtask>, &[usize]) -> Option<SubtaskPath>,
+    ) -> Result<()> {
+        let Some(Row { todo: s, path }) = self.selected_row() else {
+            self.report_no_selection();
+            return Ok(());
+        };
+        if path.is_empty() {
+            self.flash_tx
+                .send(FlashMsg::warn("Only subtasks can be indented"))?;
+            return Ok(());
+        }
+        let (s, path) = (*s, path.clone());
+
+        let before = self.todos[s].clone();
+        let todo = &mut self.todos[s];
+        let Some(new_path) = f(&mut todo.subtasks, &path) else {
+            return Ok(());
+        };
+        if self.settings.complete_parent_with_subtasks {
+            subtask::sync_parents(&mut todo.subtasks);
+        }
+
+        let id = todo.id;
+        self.history.record(ListOp::Replace {
+            before,
+            after: self.todos[s].clone(),
+        });
+        let change = format!("Restructure subtasks: {}", self.todos[s].name);
+        self.rebuild_rows(Some((id, new_path)));
+        self.save(change);
+        Ok(())
+    }
+
+    pub fn expand_selected(&mut self) {
+        let Some(t) = self.selected() else {
+            return;
+        };
+        if !t.subtasks.is_empty() && self.expanded.insert(t.id) {
+            self.rebuild_rows(self.selected_key());
+        }
+    }
+
+    /// Hides the subtasks of the selected todo and selects the todo itself.
+    pub fn collapse_selected(&mut self) {
+        let Some(id) = self.selected().map(|t| t.id) else {
+            return;
+        };
+        if self.expanded.remove(&id) {
+            self.rebuild_rows(Some((id, Vec::new())));
+        }
+    }
+
+    /// Swaps the selected todo with the closest visible todo above or below it,
+    /// so todos hidden by the tag filter keep their place.
+    fn move_todo(&mut self, up: bool) {
+        let Some(s) = self.selected_row().map(|r| r.todo) else {
+            return;
+        };
+        let visible: Vec<usize> = self
+            .rows
+            .iter()
+            .filter(|r| r.path.is_empty())
+            .map(|r| r.todo)
+            .collect();
+        let pos = visible.iter().position(|i| *i == s).unwrap();
+        let new_pos = if up {
+            pos.checked_sub(1).unwrap_or(visible.len() - 1)
+        } else if pos == visible.len() - 1 {
+            0
+        } else {
+            pos + 1
+        };
+        let new_index = visible[new_pos];
+
+        self.todos.swap(s, new_index);
+        self.rebuild_rows(Some((self.todos[new_index].id, Vec::new())));
+        self.save_to_disk();
+    }
+
+    pub fn move_todo_up(&mut self) {
+        self.move_todo(true)
     }
+
     pub fn move_todo_down(&mut self) {
-        if let Some(s) = self.list_state.inner().selected() {
-            let new_index = if s == self.todos.len() - 1 { 0 } else { s + 1 };
-            self.todos.swap(s, new_index);
-            self.list_state.select(new_index).unwrap();
-            self.save_to_disk().unwrap();
+        self.move_todo(false)
+    }
+
+    fn todo_order(&self) -> Vec<TodoId> {
+        self.todos.iter().map(|t| t.id).collect()
+    }
+
+    pub fn enter_move_mode(&mut self) {
+        self.move_mode = true;
+        self.order_before_move = self.todo_order();
+    }
+
+    pub fn leave_move_mode(&mut self) {
+        self.move_mode = false;
+        let before = std::mem::take(&mut self.order_before_move);
+        let after = self.todo_order();
+        if before != after {
+            self.history.record(ListOp::Reorder { before, after });
+            // the moves themselves are saved right away, but only committed once done
+            self.commit("Reorder todos");
         }
     }
 
-    #[inline(always)]
-    pub fn select(&mut self, selection: usize) {
-        self.list_state.select(selection).unwrap();
+    /// Applies an operation taken from the history and selects the affected todo.
+    fn apply(&mut self, op: ListOp) {
+        let selected = self.selected_key();
+        let select = match op {
+            ListOp::Add { todo, index } => {
+                let id = todo.id;
+                self.todos.insert(index.min(self.todos.len()), todo);
+                Some((id, Vec::new()))
+            }
+            ListOp::Remove { todo, .. } => {
+                if let Some(i) = self.index_of(todo.id) {
+                    self.todos.remove(i);
+                }
+                selected.filter(|(id, _)| *id != todo.id)
+            }
+            ListOp::Replace { after, .. } => {
+                let id = after.id;
+                if let Some(i) = self.index_of(id) {
+                    self.todos[i] = after;
+                }
+                // stay on the same subtask when undoing changes to it
+                selected
+                    .filter(|(selected_id, _)| *selected_id == id)
+                    .or(Some((id, Vec::new())))
+            }
+            ListOp::Reorder { after, .. } => {
+                self.todos.sort_by_key(|t| {
+                    after
+                        .iter()
+                        .position(|id| *id == t.id)
+                        .unwrap_or(usize::MAX)
+                });
+                selected
+            }
+        };
+
+        self.rebuild_rows(select);
+    }
+
+    pub fn undo(&mut self) -> Result<()> {
+        let msg = match self.history.undo() {
+            Some(op) => {
+                let change = format!("Undo: {}", op.describe());
+                let msg = format!("Undid {}", op.describe());
+                self.apply(op.inverse());
+                self.save(change);
+                FlashMsg::info(msg)
+            }
+            None => FlashMsg::warn("Nothing to undo"),
+        };
+        self.flash_tx.send(msg)?;
+        Ok(())
+    }
+
+    pub fn redo(&mut self) -> Result<()> {
+        let msg = match self.history.redo() {
+            Some(op) => {
+                let change = format!("Redo: {}", op.describe());
+                let msg = format!("Redid {}", op.describe());
+                self.apply(op);
+                self.save(change);
+                FlashMsg::info(msg)
+            }
+            None => FlashMsg::warn("Nothing to redo"),
+        };
+        self.flash_tx.send(msg)?;
+        Ok(())
+    }
+
+    pub fn select(&mut self, id: TodoId) {
+        if self.row_index(id, &[]).is_none() && !self.tag_filter.is_empty() {
+            // the todo is hidden by the filter
+            self.tag_filter.clear();
+            self.rebuild_rows(None);
+            self.flash_tx
+                .send(FlashMsg::info("Cleared tag filter"))
+                .unwrap();
+        }
+        if let Some(i) = self.row_index(id, &[]) {
+            self.list_state.select(i).unwrap();
+        }
+    }
+
+    fn title(&self) -> String {
+        let mut title = format!("Todos [{}]", self.list_name);
+        if let Ok(path) = store::file_path(&self.list_name) {
+            title += &format!(" {}", display_path(&path));
+        }
+        if let Some(view) = self.view.describe() {
+            title += &format!(" ({view})");
+        }
+        if !self.tag_filter.is_empty() {
+            title += &format!(" {}", tags::format(&self.tag_filter));
+        }
+        title
+    }
+
+    fn todo_item(&self, t: &Todo, has_subtasks: bool, dim: bool) -> ListItem<'static> {
+        let (finished, fg_style) = if t.metadata.is_recurring() {
+            ("[∞] ", Style::default().fg(self.theme.recurring_todo_title))
+        } else if t.metadata.finished {
+            ("[x] ", Style::default().fg(self.theme.completed_todo_title))
+        } else {
+            ("[ ] ", Style::default().fg(self.theme.todo_title))
+        };
+        let expander = if !has_subtasks {
+            ""
+        } else if t.subtasks.is_empty() {
+            "  "
+        } else if self.expanded.contains(&t.id) {
+            "▾ "
+        } else {
+            "▸ "
+        };
+
+        let mut spans = vec![Span::raw(expander.to_string() + finished)];
+        if let Some(style) = self.theme.priority_style(t.metadata.priority) {
+            let marker_style = if dim {
+                Style::default()
+            } else {
+                Style::default()
+                    .fg(style.color)
+                    .add_modifier(Modifier::BOLD)
+            };
+            spans.push(Span::styled(style.marker.clone(), marker_style));
+            spans.push(Span::raw(" "));
+        }
+        spans.push(Span::raw(t.name.clone() + &progress_label(&t.subtasks)));
+
+        let done = t.metadata.finished && !t.metadata.is_recurring();
+        if let Some(dd) = t.metadata.due_date.filter(|_| !done) {
+            let soon = Duration::hours(i64::from(self.settings.due_soon_hours));
+            let due_style = match DueStatus::of(dd, self.now, soon) {
+                _ if dim => Style::default(),
+                DueStatus::Overdue => Style::default()
+                    .fg(self.theme.due_overdue)
+                    .add_modifier(Modifier::BOLD),
+                DueStatus::Today => Style::default().fg(self.theme.due_today),
+                DueStatus::Soon => Style::default().fg(self.theme.due_soon),
+                DueStatus::Later => Style::default(),
+            };
+            spans.push(Span::styled(
+                format!(" ({})", due::relative_label(dd, self.now)),
+                due_style,
+            ));
+        }
+
+        for tag in t.tags.iter() {
+            let chip_style = if dim {
+                Style::default()
+            } else {
+                Style::default()
+                    .fg(self.theme.tag_fg)
+                    .bg(self.theme.tag_color(tag))
+            };
+            spans.push(Span::raw(" "));
+            spans.push(Span::styled(format!(" {tag} "), chip_style));
+        }
+
+        let fg_style = if dim { Style::default() } else { fg_style };
+        ListItem::new(Spans::from(spans)).style(fg_style)
+    }
+
+    fn subtask_item(&self, sub: &Subtask, depth: usize, dim: bool) -> ListItem<'static> {
+        let (finished, fg_style) = if sub.finished {
+            ("[x] ", Style::default().fg(self.theme.completed_todo_title))
+        } else {
+            ("[ ] ", Style::default().fg(self.theme.todo_title))
+        };
+        let line = "  ".repeat(depth + 1) + finished + &sub.name + &progress_label(&sub.children);
+
+        let fg_style = if dim { Style::default() } else { fg_style };
+        ListItem::new(Spans::from(line)).style(fg_style)
     }
 
     pub fn load_hintbar(&mut self, bar_type: BarType) {
         self.hintbars.selected = bar_type as usize;
     }
+
+    /// Rows the current hint bar takes up at the bottom of the screen.
+    pub fn hintbar_height(&self, size: Rect) -> u16 {
+        self.hintbars.items[self.hintbars.selected].height_required(size.width - 2, size.height)
+    }
+}
+
+/// Formats the `[done/total]` indicator shown next to items that have subtasks.
+fn progress_label(subtasks: &[Subtask]) -> String {
+    if subtasks.is_empty() {
+        return String::new();
+    }
+    let (done, total) = subtask::progress(subtasks);
+    format!(" [{done}/{total}]")
 }
 
 impl Component for TodoListComponent {
@@ -325,33 +1352,46 @@ impl Component for TodoListComponent {
                 [
                     Constraint::Percentage(60),
                     Constraint::Min(3),
-                    Constraint::Length(hintbar.height_required(size.width - 2, size.height)),
+                    Constraint::Length(self.hintbar_height(size)),
                 ]
                 .as_ref(),
             )
             .split(size);
 
-        let list_items: Vec<ListItem> = self
-            .todos
-            .iter()
-            .map(|t| {
-                let (finished, mut fg_style) = if t.metadata.recurring {
-                    ("[∞] ", Style::default().fg(self.theme.recurring_todo_title))
-                } else if t.metadata.finished {
-                    ("[x] ", Style::default().fg(self.theme.completed_todo_title))
-                } else {
-                    ("[ ] ", Style::default().fg(self.theme.todo_title))
-                };
-
-                if dim {
-                    fg_style = Style::default();
-                }
-
-                let line = finished.to_string() + t.name.as_ref();
-                let line = vec![Spans::from(line)];
-                ListItem::new(line).style(fg_style)
-            })
-            .collect();
+        // leave room for the expander only if there is something to expand
+        let has_subtasks = self.todos.iter().any(|t| !t.subtasks.is_empty());
+        let header_style = if dim {
+            Style::default()
+        } else {
+            Style::default()
+                .fg(self.theme.section_title)
+                .add_modifier(Modifier::BOLD)
+        };
+        let mut sections = self.sections.iter().peekable();
+        let mut list_items: Vec<ListItem> =
+            Vec::with_capacity(self.rows.len() + self.sections.len());
+        for (i, row) in self.rows.iter().enumerate() {
+            if let Some((_, title)) = sections.next_if(|(start, _)| *start == i) {
+                list_items.push(ListItem::new(Spans::from(Span::styled(
+                    *title,
+                    header_style,
+                ))));
+            }
+            let t = &self.todos[row.todo];
+            list_items.push(match subtask::get(&t.subtasks, &row.path) {
+                Some(sub) => self.subtask_item(sub, row.path.len(), dim),
+                None => self.todo_item(t, has_subtasks, dim),
+            });
+        }
+        // headers take up lines in the list but can't be selected
+        let selected = self.list_state.inner().selected().map(|s| {
+            s + self
+                .sections
+                .iter()
+                .take_while(|(start, _)| *start <= s)
+                .count()
+        });
+        self.display_state.select(selected);
 
         let border_style = if self.move_mode {
             Style::default()
@@ -379,19 +1419,19 @@ impl Component for TodoListComponent {
                 Block::default()
                     .borders(Borders::ALL)
                     .border_style(border_style)
-                    .title("Todos")
+                    .title(self.title())
                     .dim(dim),
             )
             .highlight_style(highlight_style)
             .highlight_symbol(LIST_HIGHLIGHT_SYMBOL);
-        f.render_stateful_widget(items, chunks[0], self.list_state.inner_mut());
+        f.render_stateful_widget(items, chunks[0], &mut self.display_state);
 
         let data_chunks = Layout::default()
             .direction(Direction::Horizontal)
             .constraints([Constraint::Percentage(70), Constraint::Min(30)].as_ref())
             .split(chunks[1]);
 
-        if let Some((t, _)) = self.selected() {
+        if let Some(t) = self.selected() {
             let description = StatefulParagraph::new(&*t.description)
                 .style(Style::default())
                 .block(
@@ -482,22 +1522,78 @@ impl Component for TodoListComponent {
             } else {
                 self.next();
             }
+        } else if key_match(&key, &self.keys.move_right) && !self.move_mode {
+            self.expand_selected();
+        } else if key_match(&key, &self.keys.move_left) && !self.move_mode {
+            self.collapse_selected();
         } else if key_match(&key, &self.keys.toggle_completed) {
             self.toggle_finished();
+        } else if key_match(&key, &self.keys.add_subtask) && !self.move_mode {
+            if self.selected().is_none() {
+                self.report_no_selection();
+                return Ok(AppMessage::NoAction);
+            }
+            return Ok(AppMessage::InputState(AppState::AddSubtask));
+        } else if key_match(&key, &self.keys.indent_subtask) && !self.move_mode {
+            self.indent_subtask()?;
+        } else if key_match(&key, &self.keys.outdent_subtask) && !self.move_mode {
+            self.outdent_subtask()?;
         } else if key_match(&key, &self.keys.add_todo) {
             return Ok(AppMessage::InputState(AppState::AddTodo));
         } else if key_match(&key, &self.keys.edit_todo) {
             return Ok(AppMessage::InputState(AppState::EditTodo));
         } else if key_match(&key, &self.keys.move_mode) {
-            self.move_mode = true;
+            if !self.view.is_manual() {
+                self.flash_tx.send(FlashMsg::warn(
+                    "Todos can only be moved in manual order without grouping",
+                ))?;
+                return Ok(AppMessage::NoAction);
+            }
+            self.enter_move_mode();
             return Ok(AppMessage::InputState(AppState::Move));
+        } else if key_match(&key, &self.keys.sort_mode) && !self.move_mode {
+            self.cycle_sort_mode()?;
+        } else if key_match(&key, &self.keys.group_mode) && !self.move_mode {
+            self.cycle_group_mode()?;
+        } else if key_match(&key, &self.keys.priority_up) && !self.move_mode {
+            self.change_priority(Priority::raise)?;
+        } else if key_match(&key, &self.keys.priority_down) && !self.move_mode {
+            self.change_priority(Priority::lower)?;
         } else if key_match(&key, &self.keys.find_mode) {
             return Ok(AppMessage::InputState(AppState::Find));
+        } else if key_match(&key, &self.keys.calendar_view) && !self.move_mode {
+            return Ok(AppMessage::InputState(AppState::Calendar));
+        } else if key_match(&key, &self.keys.agenda_view) && !self.move_mode {
+            return Ok(AppMessage::InputState(AppState::Agenda));
+        } else if key_match(&key, &self.keys.filter_tags) && !self.move_mode {
+            if self.all_tags().is_empty() {
+                self.flash_tx
+                    .send(FlashMsg::warn("No todos in this list have tags"))?;
+                return Ok(AppMessage::NoAction);
+            }
+            return Ok(AppMessage::InputState(AppState::TagFilter));
+        } else if key_match(&key, &self.keys.switch_list) && !self.move_mode {
+            return Ok(AppMessage::InputState(AppState::Lists));
+        } else if key_match(&key, &self.keys.move_to_list) && !self.move_mode {
+            if self.selected().is_none() {
+                self.report_no_selection();
+                return Ok(AppMessage::NoAction);
+            }
+            return Ok(AppMessage::InputState(AppState::MoveToList));
+        } else if key_match(&key, &self.keys.sync) && !self.move_mode {
+            let conflicts = self.sync()?;
+            if !conflicts.is_empty() {
+                return Ok(AppMessage::SyncConflicts(conflicts));
+            }
         } else if key_match(&key, &self.keys.remove_todo) {
             self.remove_current()?;
         } else if key_match(&key, &self.keys.submit) && self.move_mode {
-            self.move_mode = false;
+            self.leave_move_mode();
             return Ok(AppMessage::InputState(AppState::Normal));
+        } else if key_match(&key, &self.keys.undo) && !self.move_mode {
+            self.undo()?;
+        } else if key_match(&key, &self.keys.redo) && !self.move_mode {
+            self.redo()?;
         } else if key_match(&key, &self.keys.desc_scroll_up) {
             self.scroll_desc(ScrollSelection::Up);
         } else if key_match(&key, &self.keys.desc_scroll_down) {
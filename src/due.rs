@@ -0,0 +1,334 @@
+use anyhow::{bail, Result};
+use chrono::{Datelike, Duration, Months, NaiveDate, NaiveDateTime, NaiveTime, Weekday};
+
+static WEEKDAYS: [(&str, Weekday); 7] = [
+    ("monday", Weekday::Mon),
+    ("tuesday", Weekday::Tue),
+    ("wednesday", Weekday::Wed),
+    ("thursday", Weekday::Thu),
+    ("friday", Weekday::Fri),
+    ("saturday", Weekday::Sat),
+    ("sunday", Weekday::Sun),
+];
+
+static MONTHS: [&str; 12] = [
+    "january",
+    "february",
+    "march",
+    "april",
+    "may",
+    "june",
+    "july",
+    "august",
+    "september",
+    "october",
+    "november",
+    "december",
+];
+
+/// How close a due date is, which decides the color of its label.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DueStatus {
+    Overdue,
+    Today,
+    Soon,
+    Later,
+}
+
+impl DueStatus {
+    pub fn of(due: NaiveDateTime, now: NaiveDateTime, soon: Duration) -> Self {
+        if due < now {
+            Self::Overdue
+        } else if due.date() == now.date() {
+            Self::Today
+        } else if due - now <= soon {
+            Self::Soon
+        } else {
+            Self::Later
+        }
+    }
+}
+
+/// Describes a due date relative to `now`, e.g. `in 2h`, `tomorrow` or `3d overdue`.
+pub fn relative_label(due: NaiveDateTime, now: NaiveDateTime) -> String {
+    if due < now {
+        let late = now - due;
+        return if late < Duration::hours(1) {
+            format!("{}m overdue", late.num_minutes().max(1))
+        } else if late < Duration::days(1) {
+            format!("{}h overdue", late.num_hours())
+        } else {
+            format!("{}d overdue", late.num_days())
+        };
+    }
+
+    let left = due - now;
+    let days = (due.date() - now.date()).num_days();
+    if left < Duration::hours(1) {
+        format!("in {}m", left.num_minutes().max(1))
+    } else if days == 0 {
+        format!("in {}h", left.num_hours())
+    } else if days == 1 {
+        "tomorrow".into()
+    } else if days < 14 {
+        format!("in {days}d")
+    } else {
+        due.format("on %b %-d").to_string()
+    }
+}
+
+/// Parses a due date written out like `tomorrow 9am`, `next fri`, `in 3 days`,
+/// `nov 2 14:30`, `2026-11-02 14:30` or `eod`.
+/// Days without a time are due at the end of the day, except for `tonight` which is due
+/// at 20:00. Offsets like `in 2h` count from `now`.
+pub fn parse(input: &str, now: NaiveDateTime) -> Result<NaiveDateTime> {
+    let lower = input.trim().to_lowercase();
+    let words: Vec<&str> = lower.split_whitespace().filter(|w| *w != "at").collect();
+    let due = match words.as_slice() {
+        [] => None,
+        ["in", rest @ ..] => parse_offset(rest, now),
+        ["eod"] => Some(NaiveDateTime::new(now.date(), end_of_day())),
+        ["tonight"] => Some(NaiveDateTime::new(now.date(), evening())),
+        _ => match parse_day(&words, now) {
+            Some((date, [])) => Some(NaiveDateTime::new(date, end_of_day())),
+            Some((date, time)) => parse_time(&time.concat()).map(|t| NaiveDateTime::new(date, t)),
+            // a time alone is its next occurrence
+            None => parse_time(&words.concat()).map(|t| {
+                let today = NaiveDateTime::new(now.date(), t);
+                if today > now {
+                    today
+                } else {
+                    today + Duration::days(1)
+                }
+            }),
+        },
+    };
+    match due {
+        Some(due) => Ok(due),
+        None => bail!("unknown date '{}'", input.trim()),
+    }
+}
+
+/// When todos given only a day are due, so they aren't overdue for all of it.
+pub fn end_of_day() -> NaiveTime {
+    NaiveTime::from_hms_opt(23, 59, 0).unwrap()
+}
+
+fn evening() -> NaiveTime {
+    NaiveTime::from_hms_opt(20, 0, 0).unwrap()
+}
+
+/// Parses the day at the start of `words`, returning it with the words that follow.
+fn parse_day<'a, 'b>(
+    words: &'a [&'b str],
+    now: NaiveDateTime,
+) -> Option<(NaiveDate, &'a [&'b str])> {
+    let today = now.date();
+    let (first, rest) = words.split_first()?;
+    let day = match *first {
+        "today" | "tonight" => today,
+        "tomorrow" | "tmr" | "tmrw" => today.succ_opt()?,
+        "next" => {
+            let (unit, rest) = rest.split_first()?;
+            let date = match *unit {
+                "week" => today + Duration::weeks(1),
+                "month" => today.checked_add_months(Months::new(1))?,
+                "year" => today.checked_add_months(Months::new(12))?,
+                // the first one after today, a week from now if it is today's weekday
+                weekday => {
+                    let weekday = parse_weekday(weekday)?;
+                    (1..=7)
+                        .map(|n| today + Duration::days(n))
+                        .find(|d| d.weekday() == weekday)?
+                }
+            };
+            return Some((date, rest));
+        }
+        word => {
+            if let Some(weekday) = parse_weekday(word) {
+                (0..7)
+                    .map(|n| today + Duration::days(n))
+                    .find(|d| d.weekday() == weekday)?
+            } else if let Ok(date) = NaiveDate::parse_from_str(word, "%Y-%m-%d") {
+                date
+            } else {
+                // `nov 2` or `2 nov`, in the coming year if the date has passed
+                let (month, day) = match rest.first() {
+                    Some(next) => match (parse_month(word), parse_month(next)) {
+                        (Some(month), None) => (month, next.parse().ok()?),
+                        (None, Some(month)) => (month, word.parse().ok()?),
+                        _ => return None,
+                    },
+                    None => return None,
+                };
+                let date = NaiveDate::from_ymd_opt(today.year(), month, day)?;
+                let date = if date < today {
+                    NaiveDate::from_ymd_opt(today.year() + 1, month, day)?
+                } else {
+                    date
+                };
+                return Some((date, &rest[1..]));
+            }
+        }
+    };
+    Some((day, rest))
+}
+
+/// Parses `3 days`, `2h` or `a week` with an optional time of day, e.g. `2 days 9am`.
+fn parse_offset(words: &[&str], now: NaiveDateTime) -> Option<NaiveDateTime> {
+    let (amount, unit, rest) = match words {
+        [n, unit, rest @ ..] if parse_amount(n).is_some() => (parse_amount(n)?, *unit, rest),
+        [word, rest @ ..] => {
+            let split = word.find(|c: char| !c.is_ascii_digit())?;
+            let (n, unit) = word.split_at(split);
+            (n.parse().ok()?, unit, rest)
+        }
+        [] => return None,
+    };
+    let due = match unit {
+        "m" | "min" | "mins" | "minute" | "minutes" => {
+            now.checked_add_signed(Duration::minutes(amount.into()))?
+        }
+        "h" | "hr" | "hrs" | "hour" | "hours" => {
+            now.checked_add_signed(Duration::hours(amount.into()))?
+        }
+        "d" | "day" | "days" => now.checked_add_signed(Duration::days(amount.into()))?,
+        "w" | "wk" | "wks" | "week" | "weeks" => {
+            now.checked_add_signed(Duration::weeks(amount.into()))?
+        }
+        "mo" | "month" | "months" => now.checked_add_months(Months::new(amount))?,
+        "y" | "year" | "years" => now.checked_add_months(Months::new(amount.checked_mul(12)?))?,
+        _ => return None,
+    };
+    match rest {
+        [] => Some(due),
+        time => parse_time(&time.concat()).map(|t| NaiveDateTime::new(due.date(), t)),
+    }
+}
+
+fn parse_amount(s: &str) -> Option<u32> {
+    match s {
+        "a" | "an" | "one" => Some(1),
+        _ => s.parse().ok(),
+    }
+}
+
+/// Parses `9am`, `9:30pm`, `14:30`, `noon` or `midnight`.
+fn parse_time(s: &str) -> Option<NaiveTime> {
+    match s {
+        "noon" => return NaiveTime::from_hms_opt(12, 0, 0),
+        "midnight" => return Some(NaiveTime::MIN),
+        "eod" => return Some(end_of_day()),
+        _ => {}
+    }
+    let (clock, offset) = if let Some(clock) = s.strip_suffix("am") {
+        (clock, Some(0))
+    } else if let Some(clock) = s.strip_suffix("pm") {
+        (clock, Some(12))
+    } else {
+        (s, None)
+    };
+    let (h, m) = match clock.split_once(':') {
+        Some((h, m)) if m.len() == 2 => (h.parse::<u32>().ok()?, m.parse().ok()?),
+        Some(_) => return None,
+        // a bare number is only a time with am or pm
+        None if offset.is_some() => (clock.parse::<u32>().ok()?, 0),
+        None => return None,
+    };
+    let h = match offset {
+        Some(offset) if (1..=12).contains(&h) => h % 12 + offset,
+        Some(_) => return None,
+        None => h,
+    };
+    NaiveTime::from_hms_opt(h, m, 0)
+}
+
+fn parse_weekday(s: &str) -> Option<Weekday> {
+    WEEKDAYS
+        .iter()
+        .find(|(name, _)| s.len() >= 3 && name.starts_with(s))
+        .map(|(_, d)| *d)
+}
+
+fn parse_month(s: &str) -> Option<u32> {
+    let i = MONTHS
+        .iter()
+        .position(|name| s.len() >= 3 && name.starts_with(s))?;
+    Some(i as u32 + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a wednesday
+    fn now() -> NaiveDateTime {
+        dt("2026-10-14 10:00")
+    }
+
+    fn dt(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    fn parsed(s: &str) -> NaiveDateTime {
+        parse(s, now()).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    #[test]
+    fn days() {
+        assert_eq!(parsed("today"), dt("2026-10-14 23:59"));
+        assert_eq!(parsed("tonight"), dt("2026-10-14 20:00"));
+        assert_eq!(parsed("tonight 9pm"), dt("2026-10-14 21:00"));
+        assert_eq!(parsed("tomorrow 9am"), dt("2026-10-15 09:00"));
+        assert_eq!(parsed("Tomorrow at 9:30 pm"), dt("2026-10-15 21:30"));
+        assert_eq!(parsed("eod"), dt("2026-10-14 23:59"));
+        assert_eq!(parsed("2026-11-02 14:30"), dt("2026-11-02 14:30"));
+        assert_eq!(parsed("2026-11-02"), dt("2026-11-02 23:59"));
+        assert_eq!(parsed("nov 2 noon"), dt("2026-11-02 12:00"));
+        assert_eq!(parsed("2 november"), dt("2026-11-02 23:59"));
+        assert_eq!(parsed("jan 5"), dt("2027-01-05 23:59"));
+    }
+
+    #[test]
+    fn weekdays() {
+        assert_eq!(parsed("fri"), dt("2026-10-16 23:59"));
+        assert_eq!(parsed("wednesday 17:00"), dt("2026-10-14 17:00"));
+        assert_eq!(parsed("next wed"), dt("2026-10-21 23:59"));
+        assert_eq!(parsed("next fri 8am"), dt("2026-10-16 08:00"));
+        assert_eq!(parsed("next week"), dt("2026-10-21 23:59"));
+    }
+
+    #[test]
+    fn offsets() {
+        assert_eq!(parsed("in 3 days"), dt("2026-10-17 10:00"));
+        assert_eq!(parsed("in 2h"), dt("2026-10-14 12:00"));
+        assert_eq!(parsed("in 45 min"), dt("2026-10-14 10:45"));
+        assert_eq!(parsed("in a week 9am"), dt("2026-10-21 09:00"));
+        assert_eq!(parsed("in 1 month"), dt("2026-11-14 10:00"));
+    }
+
+    #[test]
+    fn times() {
+        assert_eq!(parsed("3pm"), dt("2026-10-14 15:00"));
+        assert_eq!(parsed("9am"), dt("2026-10-15 09:00"));
+        assert_eq!(parsed("12am"), dt("2026-10-15 00:00"));
+        assert_eq!(parsed("12pm"), dt("2026-10-14 12:00"));
+    }
+
+    #[test]
+    fn rejects() {
+        for s in [
+            "",
+            "soon",
+            "13pm",
+            "25:00",
+            "in days",
+            "in 3 fortnights",
+            "9",
+            "feb 30",
+            "in 4000000000 weeks",
+        ] {
+            assert!(parse(s, now()).is_err(), "{s}");
+        }
+    }
+}
@@ -0,0 +1,73 @@
+use crate::components::todo_list::Todo;
+use crate::priority::Priority;
+use anyhow::{anyhow, Error, Result};
+use chrono::NaiveDateTime;
+use std::str::FromStr;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Term {
+    Tag(String),
+    Done,
+    Open,
+    Overdue,
+    DueToday,
+    /// The priority is at least this high.
+    Priority(Priority),
+}
+
+/// A set of conditions a todo has to meet all of, parsed from terms like
+/// `#work open priority:high`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Filter(Vec<Term>);
+
+impl Filter {
+    pub fn matches(&self, t: &Todo, now: NaiveDateTime) -> bool {
+        // recurring todos are never done for good
+        let done = t.metadata.finished && !t.metadata.is_recurring();
+        self.0.iter().all(|term| match term {
+            Term::Tag(tag) => t.tags.contains(tag),
+            Term::Done => done,
+            Term::Open => !done,
+            Term::Overdue => !done && t.metadata.due_date.is_some_and(|dd| dd < now),
+            Term::DueToday => t
+                .metadata
+                .due_date
+                .is_some_and(|dd| dd.date() == now.date()),
+            Term::Priority(p) => t.metadata.priority >= *p,
+        })
+    }
+
+    /// Adds the terms of `other`, so todos have to match both filters.
+    pub fn extend(&mut self, other: Filter) {
+        self.0.extend(other.0);
+    }
+}
+
+impl FromStr for Filter {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let terms = s
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|w| !w.is_empty())
+            .map(|w| {
+                Ok(match w.to_lowercase().as_str() {
+                    "done" | "finished" => Term::Done,
+                    "open" | "unfinished" => Term::Open,
+                    "overdue" => Term::Overdue,
+                    "today" => Term::DueToday,
+                    w if w.starts_with('#') => Term::Tag(w.trim_start_matches('#').into()),
+                    w => match w.strip_prefix("priority:") {
+                        Some(p) => Term::Priority(p.parse()?),
+                        None => {
+                            return Err(anyhow!(
+                                "unknown filter '{w}', expected #tag, done, open, overdue, today or priority:<level>"
+                            ))
+                        }
+                    },
+                })
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self(terms))
+    }
+}
@@ -0,0 +1,435 @@
+use crate::components::todo_list::Todo;
+use crate::priority::Priority;
+use crate::recurrence::Recurrence;
+use anyhow::{anyhow, bail, Result};
+use chrono::{
+    DateTime, Datelike, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc, Weekday,
+};
+
+static UTC_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+static FLOATING_FORMAT: &str = "%Y%m%dT%H%M%S";
+static DATE_FORMAT: &str = "%Y%m%d";
+
+// lines longer than this many bytes are folded onto continuation lines
+static MAX_LINE_LEN: usize = 75;
+
+/// Writes the todos as the VTODO components of a single calendar. Due dates are
+/// written as floating times, since tood has no notion of time zones.
+pub fn export(todos: &[Todo]) -> String {
+    calendar(todos.iter().map(|t| (t, default_uid(t))))
+}
+
+/// Writes a calendar holding only `t`, which is how CalDAV servers store todos.
+#[cfg_attr(not(feature = "caldav"), allow(dead_code))]
+pub fn export_one(t: &Todo, uid: &str) -> String {
+    calendar(std::iter::once((t, uid.to_string())))
+}
+
+pub fn default_uid(t: &Todo) -> String {
+    format!("tood-{}-{}", t.id, t.metadata.added_at.timestamp())
+}
+
+fn calendar<'a>(todos: impl Iterator<Item = (&'a Todo, String)>) -> String {
+    let stamp = Utc::now().format(UTC_FORMAT).to_string();
+    let mut out = String::new();
+    push_line(&mut out, "BEGIN:VCALENDAR");
+    push_line(&mut out, "VERSION:2.0");
+    push_line(&mut out, "PRODID:-//tood//tood//EN");
+    for (t, uid) in todos {
+        push_vtodo(&mut out, t, &uid, &stamp);
+    }
+    push_line(&mut out, "END:VCALENDAR");
+    out
+}
+
+fn push_vtodo(out: &mut String, t: &Todo, uid: &str, stamp: &str) {
+    let md = &t.metadata;
+    push_line(out, "BEGIN:VTODO");
+    push_line(out, &format!("UID:{uid}"));
+    push_line(out, &format!("DTSTAMP:{stamp}"));
+    push_line(out, &format!("SUMMARY:{}", escape(&t.name)));
+    if !t.description.is_empty() {
+        push_line(out, &format!("DESCRIPTION:{}", escape(&t.description)));
+    }
+    push_line(out, &format!("CREATED:{}", utc(md.added_at)));
+    if let Some(ea) = md.edited_at {
+        push_line(out, &format!("LAST-MODIFIED:{}", utc(ea)));
+    }
+    if let Some(dd) = md.due_date {
+        push_line(out, &format!("DUE:{}", dd.format(FLOATING_FORMAT)));
+    }
+    if md.finished {
+        push_line(out, "STATUS:COMPLETED");
+        let completed = md.edited_at.unwrap_or(md.added_at);
+        push_line(out, &format!("COMPLETED:{}", utc(completed)));
+    } else {
+        push_line(out, "STATUS:NEEDS-ACTION");
+    }
+    if let Some(p) = priority_number(md.priority) {
+        push_line(out, &format!("PRIORITY:{p}"));
+    }
+    if !t.tags.is_empty() {
+        let tags: Vec<String> = t.tags.iter().map(|t| escape(t)).collect();
+        push_line(out, &format!("CATEGORIES:{}", tags.join(",")));
+    }
+    if let Some(rule) = &md.recurrence {
+        push_line(out, &format!("RRULE:{}", rrule(rule)));
+    }
+    push_line(out, "END:VTODO");
+}
+
+/// Reads every VTODO of the calendar, ignoring other components like events.
+pub fn import(input: &str) -> Result<Vec<Todo>> {
+    Ok(import_with_uids(input)?
+        .into_iter()
+        .map(|(t, _)| t)
+        .collect())
+}
+
+/// Like `import`, but also returns the UID of each todo, if it has one.
+pub fn import_with_uids(input: &str) -> Result<Vec<(Todo, Option<String>)>> {
+    let mut todos = Vec::new();
+    let mut current: Option<(Todo, Option<String>)> = None;
+    let mut start = None;
+    // components nested inside a todo, like alarms, are skipped
+    let mut nested = 0;
+
+    for line in unfold(input) {
+        let (name, params, value) = split_line(&line)?;
+        match (name.as_str(), value.as_str()) {
+            ("BEGIN", "VTODO") if current.is_none() => {
+                current = Some((Todo::default(), None));
+                start = None;
+            }
+            ("END", "VTODO") if nested == 0 => {
+                if let Some((mut t, uid)) = current.take() {
+                    // monthly rules without a day repeat on the day of the first occurrence,
+                    // and can't be kept if there is none
+                    if t.metadata.recurrence == Some(Recurrence::Monthly(0)) {
+                        t.metadata.recurrence = t
+                            .metadata
+                            .due_date
+                            .or(start)
+                            .map(|first| Recurrence::Monthly(first.day()));
+                    }
+                    todos.push((t, uid));
+                }
+            }
+            ("BEGIN", _) if current.is_some() => nested += 1,
+            ("END", _) if current.is_some() => nested -= 1,
+            _ => {
+                if let (Some((t, uid)), 0) = (current.as_mut(), nested) {
+                    if name == "UID" {
+                        *uid = Some(value);
+                    } else if name == "DTSTART" {
+                        start = Some(parse_time(&value, &params)?.into_naive());
+                    } else {
+                        set_property(t, &name, &params, &value)?;
+                    }
+                }
+            }
+        }
+    }
+    if current.is_some() {
+        bail!("VTODO is missing its END line");
+    }
+    Ok(todos)
+}
+
+fn set_property(t: &mut Todo, name: &str, params: &str, value: &str) -> Result<()> {
+    let md = &mut t.metadata;
+    match name {
+        "SUMMARY" => t.name = unescape(value),
+        "DESCRIPTION" => t.description = unescape(value),
+        "CREATED" => md.added_at = parse_time(value, params)?.into_local(),
+        "LAST-MODIFIED" => md.edited_at = Some(parse_time(value, params)?.into_local()),
+        "DUE" => md.due_date = Some(parse_time(value, params)?.into_naive()),
+        "STATUS" => md.finished = value == "COMPLETED",
+        "COMPLETED" => md.finished = true,
+        "PRIORITY" => {
+            let p: u8 = value
+                .parse()
+                .map_err(|_| anyhow!("invalid PRIORITY '{value}'"))?;
+            md.priority = priority_from_number(p);
+        }
+        "CATEGORIES" => {
+            // tags can't contain whitespace, unlike categories
+            for c in value.split(',') {
+                let tag = unescape(c).trim().to_lowercase().replace(' ', "-");
+                if !tag.is_empty() && !t.tags.contains(&tag) {
+                    t.tags.push(tag);
+                }
+            }
+        }
+        "RRULE" => md.recurrence = parse_rrule(value)?,
+        _ => {}
+    }
+    Ok(())
+}
+
+fn push_line(out: &mut String, line: &str) {
+    let mut len = 0;
+    for c in line.chars() {
+        if len + c.len_utf8() > MAX_LINE_LEN {
+            out.push_str("\r\n ");
+            len = 1;
+        }
+        out.push(c);
+        len += c.len_utf8();
+    }
+    out.push_str("\r\n");
+}
+
+fn unfold(input: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in input.lines() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some(last)) => last.push_str(rest),
+            _ if line.trim().is_empty() => {}
+            _ => lines.push(line.to_string()),
+        }
+    }
+    lines
+}
+
+/// Splits a content line into its upper cased name, its parameters and its value.
+fn split_line(line: &str) -> Result<(String, String, String)> {
+    let mut quoted = false;
+    let colon = line
+        .char_indices()
+        .find(|(_, c)| {
+            if *c == '"' {
+                quoted = !quoted;
+            }
+            *c == ':' && !quoted
+        })
+        .map(|(i, _)| i)
+        .ok_or_else(|| anyhow!("invalid line '{line}'"))?;
+    let (head, value) = (&line[..colon], &line[colon + 1..]);
+    let (name, params) = head.split_once(';').unwrap_or((head, ""));
+    Ok((
+        name.to_uppercase(),
+        params.to_uppercase(),
+        value.to_string(),
+    ))
+}
+
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+fn unescape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n' | 'N') => out.push('\n'),
+            Some(c) => out.push(c),
+            None => {}
+        }
+    }
+    out
+}
+
+fn utc(dt: DateTime<Local>) -> String {
+    dt.with_timezone(&Utc).format(UTC_FORMAT).to_string()
+}
+
+enum Time {
+    Utc(DateTime<Utc>),
+    Floating(NaiveDateTime),
+}
+
+impl Time {
+    fn into_local(self) -> DateTime<Local> {
+        match self {
+            Self::Utc(dt) => dt.with_timezone(&Local),
+            Self::Floating(dt) => Local
+                .from_local_datetime(&dt)
+                .earliest()
+                .unwrap_or_else(Local::now),
+        }
+    }
+
+    fn into_naive(self) -> NaiveDateTime {
+        match self {
+            Self::Utc(dt) => dt.with_timezone(&Local).naive_local(),
+            Self::Floating(dt) => dt,
+        }
+    }
+}
+
+/// Parses date-times in UTC or floating time, and plain dates. Times with a
+/// `TZID` are read as local times.
+fn parse_time(value: &str, params: &str) -> Result<Time> {
+    if params.contains("VALUE=DATE") && !params.contains("VALUE=DATE-TIME") {
+        let d = NaiveDate::parse_from_str(value, DATE_FORMAT)
+            .map_err(|_| anyhow!("invalid date '{value}'"))?;
+        return Ok(Time::Floating(d.and_time(NaiveTime::MIN)));
+    }
+    if let Ok(dt) = NaiveDateTime::parse_from_str(value, UTC_FORMAT) {
+        return Ok(Time::Utc(Utc.from_utc_datetime(&dt)));
+    }
+    if let Ok(dt) = NaiveDateTime::parse_from_str(value, FLOATING_FORMAT) {
+        return Ok(Time::Floating(dt));
+    }
+    match NaiveDate::parse_from_str(value, DATE_FORMAT) {
+        Ok(d) => Ok(Time::Floating(d.and_time(NaiveTime::MIN))),
+        Err(_) => bail!("invalid date '{value}'"),
+    }
+}
+
+/// Maps priorities onto the 1 (highest) to 9 (lowest) scale, where 0 means undefined.
+fn priority_number(p: Priority) -> Option<u8> {
+    match p {
+        Priority::None => None,
+        Priority::Urgent => Some(1),
+        Priority::High => Some(3),
+        Priority::Medium => Some(5),
+        Priority::Low => Some(7),
+    }
+}
+
+fn priority_from_number(p: u8) -> Priority {
+    match p {
+        0 => Priority::None,
+        1 => Priority::Urgent,
+        2..=4 => Priority::High,
+        5 => Priority::Medium,
+        _ => Priority::Low,
+    }
+}
+
+static BYDAY: [(Weekday, &str); 7] = [
+    (Weekday::Mon, "MO"),
+    (Weekday::Tue, "TU"),
+    (Weekday::Wed, "WE"),
+    (Weekday::Thu, "TH"),
+    (Weekday::Fri, "FR"),
+    (Weekday::Sat, "SA"),
+    (Weekday::Sun, "SU"),
+];
+
+fn rrule(rule: &Recurrence) -> String {
+    match rule {
+        Recurrence::Daily => "FREQ=DAILY".into(),
+        Recurrence::EveryNDays(n) => format!("FREQ=DAILY;INTERVAL={n}"),
+        Recurrence::Weekly(days) if days.is_empty() => "FREQ=WEEKLY".into(),
+        Recurrence::Weekly(days) => {
+            let days: Vec<&str> = BYDAY
+                .iter()
+                .filter(|(d, _)| days.contains(d))
+                .map(|(_, name)| *name)
+                .collect();
+            format!("FREQ=WEEKLY;BYDAY={}", days.join(","))
+        }
+        Recurrence::Monthly(day) => format!("FREQ=MONTHLY;BYMONTHDAY={day}"),
+    }
+}
+
+/// Reads the subset of recurrence rules tood can represent. Other rules, like yearly
+/// ones or every other month, leave the todo without a recurrence. Monthly rules without
+/// a day come back as `Monthly(0)`, for the caller to fill in from the first occurrence.
+fn parse_rrule(value: &str) -> Result<Option<Recurrence>> {
+    let (mut freq, mut interval, mut byday, mut bymonthday) = ("", 1, Vec::new(), None);
+    for part in value.split(';') {
+        let (key, val) = part
+            .split_once('=')
+            .ok_or_else(|| anyhow!("invalid RRULE '{value}'"))?;
+        match key.to_uppercase().as_str() {
+            "FREQ" => freq = val,
+            "INTERVAL" => {
+                interval = val
+                    .parse()
+                    .map_err(|_| anyhow!("invalid RRULE interval '{val}'"))?
+            }
+            "BYDAY" => {
+                // ordinals like `1MO` are dropped, leaving just the weekday
+                for day in val.split(',') {
+                    let name = day.trim_start_matches(|c: char| !c.is_ascii_alphabetic());
+                    if let Some((d, _)) = BYDAY.iter().find(|(_, n)| n.eq_ignore_ascii_case(name)) {
+                        byday.push(*d);
+                    }
+                }
+            }
+            "BYMONTHDAY" => bymonthday = val.split(',').next().and_then(|d| d.parse().ok()),
+            _ => {}
+        }
+    }
+
+    let rule = match freq.to_uppercase().as_str() {
+        "DAILY" if interval > 1 => Recurrence::EveryNDays(interval),
+        "DAILY" => Recurrence::Daily,
+        "WEEKLY" if interval > 1 && byday.is_empty() => match interval.checked_mul(7) {
+            Some(days) => Recurrence::EveryNDays(days),
+            None => return Ok(None),
+        },
+        "WEEKLY" if interval == 1 => Recurrence::Weekly(byday),
+        // nth weekdays of the month can't be represented either
+        "MONTHLY" if interval == 1 && byday.is_empty() => {
+            Recurrence::Monthly(bymonthday.unwrap_or(0))
+        }
+        _ => return Ok(None),
+    };
+    Ok(Some(rule))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn import_rule(props: &str) -> Option<Recurrence> {
+        let input = format!(
+            "BEGIN:VCALENDAR\r\nBEGIN:VTODO\r\nSUMMARY:t\r\n{props}END:VTODO\r\nEND:VCALENDAR\r\n"
+        );
+        import(&input).unwrap().remove(0).metadata.recurrence
+    }
+
+    #[test]
+    fn imports_rules_tood_can_represent() {
+        assert_eq!(import_rule("RRULE:FREQ=DAILY\r\n"), Some(Recurrence::Daily));
+        assert_eq!(
+            import_rule("RRULE:FREQ=WEEKLY;INTERVAL=2\r\n"),
+            Some(Recurrence::EveryNDays(14))
+        );
+        assert_eq!(
+            import_rule("RRULE:FREQ=WEEKLY;BYDAY=MO,FR\r\n"),
+            Some(Recurrence::Weekly(vec![Weekday::Mon, Weekday::Fri]))
+        );
+        assert_eq!(
+            import_rule("RRULE:FREQ=MONTHLY;BYMONTHDAY=15\r\n"),
+            Some(Recurrence::Monthly(15))
+        );
+    }
+
+    #[test]
+    fn drops_rules_it_cannot_represent() {
+        assert_eq!(import_rule("RRULE:FREQ=MONTHLY;INTERVAL=3\r\n"), None);
+        assert_eq!(
+            import_rule("RRULE:FREQ=WEEKLY;INTERVAL=2;BYDAY=MO\r\n"),
+            None
+        );
+        assert_eq!(import_rule("RRULE:FREQ=MONTHLY;BYDAY=1MO\r\n"), None);
+        assert_eq!(import_rule("RRULE:FREQ=YEARLY\r\n"), None);
+    }
+
+    #[test]
+    fn monthly_rules_without_a_day_use_the_first_occurrence() {
+        assert_eq!(
+            import_rule("DUE:20261107T090000\r\nRRULE:FREQ=MONTHLY\r\n"),
+            Some(Recurrence::Monthly(7))
+        );
+        assert_eq!(
+            import_rule("DTSTART;VALUE=DATE:20261121\r\nRRULE:FREQ=MONTHLY\r\n"),
+            Some(Recurrence::Monthly(21))
+        );
+        assert_eq!(import_rule("RRULE:FREQ=MONTHLY\r\n"), None);
+    }
+}
@@ -0,0 +1,263 @@
+use crate::components::todo_list::{Todo, TodoId, TodoMetadata};
+use crate::priority::Priority;
+use crate::recurrence::Recurrence;
+use crate::remind::LeadTime;
+use crate::subtask::Subtask;
+use anyhow::{bail, Result};
+use chrono::{DateTime, Local, NaiveDateTime, Weekday};
+use serde::{Deserialize, Serialize};
+
+/// Bumped whenever a field is renamed or removed or changes its meaning.
+/// Adding fields does not change the version. See the README for the schema.
+pub static SCHEMA_VERSION: u32 = 1;
+
+// The schema has its own types, so changes to how lists are stored don't leak into exports.
+
+#[derive(Serialize)]
+struct Export<'a> {
+    version: u32,
+    list: &'a str,
+    exported_at: DateTime<Local>,
+    todos: Vec<JsonTodo>,
+}
+
+#[derive(Deserialize)]
+struct Import {
+    version: u32,
+    todos: Vec<JsonTodo>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct JsonTodo {
+    #[serde(default)]
+    id: TodoId,
+    name: String,
+    #[serde(default)]
+    description: String,
+    metadata: JsonMetadata,
+    #[serde(default)]
+    subtasks: Vec<JsonSubtask>,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct JsonMetadata {
+    added_at: DateTime<Local>,
+    #[serde(default)]
+    edited_at: Option<DateTime<Local>>,
+    #[serde(default)]
+    due_date: Option<NaiveDateTime>,
+    #[serde(default)]
+    priority: JsonPriority,
+    #[serde(default)]
+    recurrence: Option<JsonRecurrence>,
+    /// Minutes.
+    #[serde(default)]
+    remind_before: Option<u32>,
+    #[serde(default)]
+    last_completed: Option<DateTime<Local>>,
+    #[serde(default)]
+    finished: bool,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+enum JsonPriority {
+    #[default]
+    None,
+    Low,
+    Medium,
+    High,
+    Urgent,
+}
+
+#[derive(Serialize, Deserialize)]
+enum JsonRecurrence {
+    Daily,
+    Weekly(Vec<Weekday>),
+    Monthly(u32),
+    EveryNDays(u32),
+}
+
+#[derive(Serialize, Deserialize)]
+struct JsonSubtask {
+    name: String,
+    #[serde(default)]
+    finished: bool,
+    #[serde(default)]
+    children: Vec<JsonSubtask>,
+}
+
+impl From<&Todo> for JsonTodo {
+    fn from(t: &Todo) -> Self {
+        let md = &t.metadata;
+        Self {
+            id: t.id,
+            name: t.name.clone(),
+            description: t.description.clone(),
+            metadata: JsonMetadata {
+                added_at: md.added_at,
+                edited_at: md.edited_at,
+                due_date: md.due_date,
+                priority: match md.priority {
+                    Priority::None => JsonPriority::None,
+                    Priority::Low => JsonPriority::Low,
+                    Priority::Medium => JsonPriority::Medium,
+                    Priority::High => JsonPriority::High,
+                    Priority::Urgent => JsonPriority::Urgent,
+                },
+                recurrence: md.recurrence.as_ref().map(|r| match r {
+                    Recurrence::Daily => JsonRecurrence::Daily,
+                    Recurrence::Weekly(days) => JsonRecurrence::Weekly(days.clone()),
+                    Recurrence::Monthly(day) => JsonRecurrence::Monthly(*day),
+                    Recurrence::EveryNDays(n) => JsonRecurrence::EveryNDays(*n),
+                }),
+                remind_before: md.remind_before.map(|l| l.0),
+                last_completed: md.last_completed,
+                finished: md.finished,
+            },
+            subtasks: t.subtasks.iter().map(JsonSubtask::from).collect(),
+            tags: t.tags.clone(),
+        }
+    }
+}
+
+impl From<JsonTodo> for Todo {
+    fn from(t: JsonTodo) -> Self {
+        let md = t.metadata;
+        Self {
+            id: t.id,
+            name: t.name,
+            description: t.description,
+            metadata: TodoMetadata {
+                added_at: md.added_at,
+                edited_at: md.edited_at,
+                due_date: md.due_date,
+                priority: match md.priority {
+                    JsonPriority::None => Priority::None,
+                    JsonPriority::Low => Priority::Low,
+                    JsonPriority::Medium => Priority::Medium,
+                    JsonPriority::High => Priority::High,
+                    JsonPriority::Urgent => Priority::Urgent,
+                },
+                recurrence: md.recurrence.map(|r| match r {
+                    JsonRecurrence::Daily => Recurrence::Daily,
+                    JsonRecurrence::Weekly(days) => Recurrence::Weekly(days),
+                    JsonRecurrence::Monthly(day) => Recurrence::Monthly(day),
+                    JsonRecurrence::EveryNDays(n) => Recurrence::EveryNDays(n),
+                }),
+                remind_before: md.remind_before.map(LeadTime),
+                last_completed: md.last_completed,
+                finished: md.finished,
+                ..Default::default()
+            },
+            subtasks: t.subtasks.into_iter().map(Subtask::from).collect(),
+            tags: t.tags,
+        }
+    }
+}
+
+impl From<&Subtask> for JsonSubtask {
+    fn from(s: &Subtask) -> Self {
+        Self {
+            name: s.name.clone(),
+            finished: s.finished,
+            children: s.children.iter().map(JsonSubtask::from).collect(),
+        }
+    }
+}
+
+impl From<JsonSubtask> for Subtask {
+    fn from(s: JsonSubtask) -> Self {
+        Self {
+            name: s.name,
+            finished: s.finished,
+            children: s.children.into_iter().map(Subtask::from).collect(),
+        }
+    }
+}
+
+pub fn export(list: &str, todos: &[Todo]) -> String {
+    let export = Export {
+        version: SCHEMA_VERSION,
+        list,
+        exported_at: Local::now(),
+        todos: todos.iter().map(JsonTodo::from).collect(),
+    };
+    // todos only hold strings, numbers and maps with string keys, which always serialize
+    let mut out = serde_json::to_string_pretty(&export).unwrap();
+    out.push('\n');
+    out
+}
+
+pub fn import(input: &str) -> Result<Vec<Todo>> {
+    let import: Import = serde_json::from_str(input)?;
+    if import.version > SCHEMA_VERSION {
+        bail!(
+            "the file uses version {} of the export schema, this tood only knows up to version {SCHEMA_VERSION}",
+            import.version
+        );
+    }
+    Ok(import.todos.into_iter().map(Todo::from).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+
+    #[test]
+    fn pins_the_schema() {
+        let added_at = DateTime::parse_from_rfc3339("2026-10-18T09:00:00+02:00")
+            .unwrap()
+            .with_timezone(&Local);
+        let mut sub = Subtask::new("collect numbers".into());
+        sub.finished = true;
+        let todo = Todo {
+            id: 1,
+            name: "Write report".into(),
+            description: "numbers from Q3".into(),
+            metadata: TodoMetadata {
+                added_at,
+                edited_at: None,
+                due_date: NaiveDateTime::parse_from_str("2026-10-20 18:00", "%Y-%m-%d %H:%M").ok(),
+                priority: Priority::High,
+                recurrence: Some(Recurrence::Weekly(vec![Weekday::Mon])),
+                remind_before: Some(LeadTime(60)),
+                ..Default::default()
+            },
+            subtasks: vec![sub],
+            tags: vec!["work".into()],
+        };
+
+        let out = export("work", std::slice::from_ref(&todo));
+        let mut value: Value = serde_json::from_str(&out).unwrap();
+        assert!(value["exported_at"].as_str().is_some());
+        value.as_object_mut().unwrap().remove("exported_at");
+        assert_eq!(
+            value,
+            json!({
+                "version": 1,
+                "list": "work",
+                "todos": [{
+                    "id": 1,
+                    "name": "Write report",
+                    "description": "numbers from Q3",
+                    "metadata": {
+                        "added_at": serde_json::to_value(added_at).unwrap(),
+                        "edited_at": null,
+                        "due_date": "2026-10-20T18:00:00",
+                        "priority": "High",
+                        "recurrence": { "Weekly": ["Mon"] },
+                        "remind_before": 60,
+                        "last_completed": null,
+                        "finished": false
+                    },
+                    "subtasks": [{ "name": "collect numbers", "finished": true, "children": [] }],
+                    "tags": ["work"]
+                }]
+            })
+        );
+        assert_eq!(import(&out).unwrap(), [todo]);
+    }
+}
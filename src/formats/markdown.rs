@@ -0,0 +1,49 @@
+use crate::components::todo_list::Todo;
+use crate::priority::Priority;
+use crate::subtask::Subtask;
+use crate::tags;
+
+static DUE_FORMAT: &str = "%Y-%m-%d %H:%M";
+
+/// Writes the todos as a checklist headed by the list name, with descriptions
+/// and subtasks indented below their todo.
+pub fn export(list: &str, todos: &[Todo]) -> String {
+    let mut out = format!("# {list}\n\n");
+    for t in todos {
+        let md = &t.metadata;
+        let check = if md.finished && !md.is_recurring() {
+            'x'
+        } else {
+            ' '
+        };
+        out.push_str(&format!("- [{check}] {}", t.name));
+        if md.priority != Priority::None {
+            out.push_str(&format!(" ({})", md.priority));
+        }
+        if let Some(dd) = md.due_date {
+            out.push_str(&format!(" (due {})", dd.format(DUE_FORMAT)));
+        }
+        if !t.tags.is_empty() {
+            out.push_str(&format!(" {}", tags::format(&t.tags)));
+        }
+        out.push('\n');
+
+        for line in t.description.lines() {
+            if line.trim().is_empty() {
+                out.push('\n');
+            } else {
+                out.push_str(&format!("  {line}\n"));
+            }
+        }
+        push_subtasks(&mut out, &t.subtasks, 1);
+    }
+    out
+}
+
+fn push_subtasks(out: &mut String, items: &[Subtask], depth: usize) {
+    for s in items {
+        let check = if s.finished { 'x' } else { ' ' };
+        out.push_str(&format!("{}- [{check}] {}\n", "  ".repeat(depth), s.name));
+        push_subtasks(out, &s.children, depth + 1);
+    }
+}
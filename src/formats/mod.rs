@@ -0,0 +1,63 @@
+pub mod ical;
+pub mod json;
+pub mod markdown;
+pub mod todotxt;
+
+use crate::components::todo_list::Todo;
+use anyhow::{bail, Error, Result};
+use std::path::Path;
+use std::str::FromStr;
+
+/// A file format todos can be imported from and exported to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    TodoTxt,
+    ICal,
+    Json,
+    Markdown,
+}
+
+impl Format {
+    /// Guesses the format of a file from its extension.
+    pub fn detect(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "txt" => Some(Self::TodoTxt),
+            "ics" => Some(Self::ICal),
+            "json" => Some(Self::Json),
+            "md" => Some(Self::Markdown),
+            _ => None,
+        }
+    }
+
+    pub fn export(&self, list: &str, todos: &[Todo]) -> String {
+        match self {
+            Self::TodoTxt => todotxt::export(todos),
+            Self::ICal => ical::export(todos),
+            Self::Json => json::export(list, todos),
+            Self::Markdown => markdown::export(list, todos),
+        }
+    }
+
+    pub fn import(&self, input: &str) -> Result<Vec<Todo>> {
+        match self {
+            Self::TodoTxt => todotxt::import(input),
+            Self::ICal => ical::import(input),
+            Self::Json => json::import(input),
+            Self::Markdown => bail!("markdown can only be exported"),
+        }
+    }
+}
+
+impl FromStr for Format {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(match s.trim().to_lowercase().as_str() {
+            "todotxt" | "todo.txt" => Self::TodoTxt,
+            "ical" | "ics" => Self::ICal,
+            "json" => Self::Json,
+            "markdown" | "md" => Self::Markdown,
+            _ => bail!("unknown format '{s}', expected todotxt, ical, json or markdown"),
+        })
+    }
+}
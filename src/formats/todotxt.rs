@@ -0,0 +1,343 @@
+use crate::components::todo_list::Todo;
+use crate::priority::Priority;
+use crate::recurrence::Recurrence;
+use crate::subtask::Subtask;
+use anyhow::{anyhow, bail, Result};
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone};
+use std::collections::HashMap;
+
+static DATE_FORMAT: &str = "%Y-%m-%d";
+static DUE_TIME_FORMAT: &str = "%Y-%m-%dT%H:%M";
+
+/// Writes one [todo.txt](https://github.com/todotxt/todo.txt) line per todo. Priorities
+/// `A` to `D` stand for urgent to low, tags become `+project` tokens unless they are
+/// `@context` tags, which keep their `@`. Recurrence is kept in a `repeat:` key, and
+/// subtasks get lines of their own pointing at their parent's `id:` with `p:`.
+/// The creation date is written without its time, which is lost.
+pub fn export(todos: &[Todo]) -> String {
+    let mut out = String::new();
+    for t in todos {
+        out.push_str(&line(t));
+        out.push('\n');
+        subtask_lines(&t.subtasks, &t.id.to_string(), &mut out);
+    }
+    out
+}
+
+fn subtask_lines(subtasks: &[Subtask], parent: &str, out: &mut String) {
+    for (i, sub) in subtasks.iter().enumerate() {
+        let id = format!("{parent}.{}", i + 1);
+        let mut parts = Vec::new();
+        if sub.finished {
+            parts.push("x".to_string());
+        }
+        parts.push(sub.name.clone());
+        if !sub.children.is_empty() {
+            parts.push(format!("id:{id}"));
+        }
+        parts.push(format!("p:{parent}"));
+        out.push_str(&parts.join(" "));
+        out.push('\n');
+        subtask_lines(&sub.children, &id, out);
+    }
+}
+
+fn line(t: &Todo) -> String {
+    let md = &t.metadata;
+    let mut parts = Vec::new();
+    let letter = priority_letter(md.priority);
+
+    if md.finished {
+        parts.push("x".to_string());
+        let completed = md.edited_at.unwrap_or(md.added_at);
+        parts.push(completed.format(DATE_FORMAT).to_string());
+    } else if let Some(l) = letter {
+        parts.push(format!("({l})"));
+    }
+    parts.push(md.added_at.format(DATE_FORMAT).to_string());
+    parts.push(t.name.clone());
+
+    for tag in t.tags.iter() {
+        if tag.starts_with('@') {
+            parts.push(tag.clone());
+        } else {
+            parts.push(format!("+{tag}"));
+        }
+    }
+    if let Some(dd) = md.due_date {
+        let due = if dd.time() == NaiveTime::MIN {
+            dd.format(DATE_FORMAT)
+        } else {
+            dd.format(DUE_TIME_FORMAT)
+        };
+        parts.push(format!("due:{due}"));
+    }
+    // finished tasks lose their `(A)` prefix, so the priority is kept in a tag instead
+    if let (true, Some(l)) = (md.finished, letter) {
+        parts.push(format!("pri:{l}"));
+    }
+    if let Some(rule) = &md.recurrence {
+        parts.push(format!("repeat:{}", rule.to_string().replace(' ', "-")));
+    }
+    if !t.subtasks.is_empty() {
+        parts.push(format!("id:{}", t.id));
+    }
+    parts.join(" ")
+}
+
+/// A parsed line whose name is still split into words. The `id:` and `p:` words link
+/// subtasks to their parents and are only left out of the name if they match another line.
+struct Line {
+    todo: Todo,
+    words: Vec<String>,
+    id: Option<usize>,
+    parent: Option<usize>,
+}
+
+impl Line {
+    fn key(&self, word: Option<usize>, prefix: &str) -> Option<&str> {
+        self.words[word?].strip_prefix(prefix)
+    }
+}
+
+pub fn import(input: &str) -> Result<Vec<Todo>> {
+    let mut lines = input
+        .lines()
+        .enumerate()
+        .filter(|(_, l)| !l.trim().is_empty())
+        .map(|(i, l)| {
+            parse_line(l)
+                .map(Some)
+                .map_err(|e| anyhow!("line {}: {e}", i + 1))
+        })
+        .collect::<Result<Vec<Option<Line>>>>()?;
+
+    let ids: HashMap<String, usize> = lines
+        .iter()
+        .enumerate()
+        .filter_map(|(i, l)| {
+            let l = l.as_ref()?;
+            Some((l.key(l.id, "id:")?.to_string(), i))
+        })
+        .collect();
+    let mut children = vec![Vec::new(); lines.len()];
+    let mut linked = vec![false; lines.len()];
+    let mut roots = Vec::new();
+    for (i, l) in lines.iter().enumerate() {
+        let parent = l.as_ref().and_then(|l| ids.get(l.key(l.parent, "p:")?));
+        match parent {
+            Some(&p) if p != i => {
+                children[p].push(i);
+                linked[i] = true;
+            }
+            _ => roots.push(i),
+        }
+    }
+    for (i, l) in lines.iter_mut().enumerate() {
+        let Some(l) = l else { continue };
+        let links = [(l.id, !children[i].is_empty()), (l.parent, linked[i])];
+        let skip: Vec<usize> = links
+            .into_iter()
+            .filter_map(|(word, used)| word.filter(|_| used))
+            .collect();
+        l.todo.name = l
+            .words
+            .iter()
+            .enumerate()
+            .filter(|(w, _)| !skip.contains(w))
+            .map(|(_, word)| word.as_str())
+            .collect::<Vec<_>>()
+            .join(" ");
+    }
+    // lines whose parents point at each other end up as todos of their own
+    roots.extend(0..lines.len());
+
+    let mut todos = Vec::new();
+    for i in roots {
+        if let Some(l) = lines[i].take() {
+            let mut todo = l.todo;
+            todo.subtasks = take_subtasks(i, &children, &mut lines);
+            todos.push(todo);
+        }
+    }
+    Ok(todos)
+}
+
+fn take_subtasks(
+    parent: usize,
+    children: &[Vec<usize>],
+    lines: &mut [Option<Line>],
+) -> Vec<Subtask> {
+    let mut subtasks = Vec::new();
+    for &c in children[parent].iter() {
+        if let Some(l) = lines[c].take() {
+            subtasks.push(Subtask {
+                name: l.todo.name,
+                finished: l.todo.metadata.finished,
+                children: take_subtasks(c, children, lines),
+            });
+        }
+    }
+    subtasks
+}
+
+fn parse_line(line: &str) -> Result<Line> {
+    let (mut id, mut parent) = (None, None);
+    let mut todo = Todo::default();
+    let md = &mut todo.metadata;
+    let mut tokens = line.split_whitespace().peekable();
+
+    if tokens.next_if_eq(&"x").is_some() {
+        md.finished = true;
+        if let Some(d) = tokens.next_if(|t| parse_date(t).is_some()) {
+            md.edited_at = parse_date(d).and_then(local_midnight);
+        }
+    } else if let Some(p) = tokens.next_if(|t| parse_priority(t).is_some()) {
+        md.priority = parse_priority(p).unwrap();
+    }
+    if let Some(d) = tokens.next_if(|t| parse_date(t).is_some()) {
+        if let Some(added_at) = parse_date(d).and_then(local_midnight) {
+            md.added_at = added_at;
+        }
+    }
+
+    let mut words = Vec::new();
+    for token in tokens {
+        let tag = if token.len() < 2 {
+            None
+        } else if let Some(project) = token.strip_prefix('+') {
+            Some(project.to_lowercase())
+        } else if token.starts_with('@') {
+            Some(token.to_lowercase())
+        } else {
+            None
+        };
+        if let Some(tag) = tag {
+            if !todo.tags.contains(&tag) {
+                todo.tags.push(tag);
+            }
+        } else if let Some(due) = token.strip_prefix("due:") {
+            todo.metadata.due_date = Some(parse_due(due)?);
+        } else if let (true, Some(l)) = (todo.metadata.finished, token.strip_prefix("pri:")) {
+            todo.metadata.priority = parse_priority(&format!("({l})"))
+                .ok_or_else(|| anyhow!("invalid priority '{l}'"))?;
+        } else if let Some(rule) = token.strip_prefix("repeat:") {
+            todo.metadata.recurrence = Some(rule.replace('-', " ").parse::<Recurrence>()?);
+        } else {
+            if token.starts_with("id:") {
+                id = Some(words.len());
+            } else if token.starts_with("p:") {
+                parent = Some(words.len());
+            }
+            words.push(token.to_string());
+        }
+    }
+
+    if words.is_empty() {
+        bail!("todo has no text");
+    }
+    Ok(Line {
+        todo,
+        words,
+        id,
+        parent,
+    })
+}
+
+fn priority_letter(p: Priority) -> Option<char> {
+    match p {
+        Priority::None => None,
+        Priority::Urgent => Some('A'),
+        Priority::High => Some('B'),
+        Priority::Medium => Some('C'),
+        Priority::Low => Some('D'),
+    }
+}
+
+/// Parses `(A)` style priorities, where everything below `C` counts as low.
+fn parse_priority(s: &str) -> Option<Priority> {
+    let letter = s.strip_prefix('(')?.strip_suffix(')')?;
+    match letter {
+        "A" => Some(Priority::Urgent),
+        "B" => Some(Priority::High),
+        "C" => Some(Priority::Medium),
+        l if l.len() == 1 && l.chars().all(|c| c.is_ascii_uppercase()) => Some(Priority::Low),
+        _ => None,
+    }
+}
+
+fn parse_date(s: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(s, DATE_FORMAT).ok()
+}
+
+fn parse_due(s: &str) -> Result<NaiveDateTime> {
+    if let Ok(dt) = NaiveDateTime::parse_from_str(s, DUE_TIME_FORMAT) {
+        return Ok(dt);
+    }
+    match parse_date(s) {
+        Some(d) => Ok(d.and_time(NaiveTime::MIN)),
+        None => bail!("invalid due date '{s}'"),
+    }
+}
+
+fn local_midnight(d: NaiveDate) -> Option<DateTime<Local>> {
+    Local
+        .from_local_datetime(&d.and_time(NaiveTime::MIN))
+        .earliest()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Weekday;
+
+    #[test]
+    fn round_trips() {
+        let mut sub = Subtask::new("pack".into());
+        sub.children = vec![Subtask::new("charger".into()), Subtask::new("socks".into())];
+        sub.children[1].finished = true;
+        let mut todo = Todo {
+            id: 3,
+            name: "plan trip".into(),
+            tags: vec!["travel".into(), "@home".into()],
+            subtasks: vec![sub, Subtask::new("book hotel".into())],
+            ..Default::default()
+        };
+        let md = &mut todo.metadata;
+        md.priority = Priority::High;
+        md.due_date = NaiveDateTime::parse_from_str("2026-11-02 14:30", "%Y-%m-%d %H:%M").ok();
+        md.recurrence = Some(Recurrence::Weekly(vec![Weekday::Mon, Weekday::Fri]));
+        let other = Todo {
+            name: "call mum".into(),
+            ..Default::default()
+        };
+
+        let out = export(&[todo.clone(), other.clone()]);
+        let imported = import(&out).unwrap();
+        assert_eq!(imported.len(), 2, "{out}");
+        let t = &imported[0];
+        assert_eq!(t.name, todo.name);
+        assert_eq!(t.tags, todo.tags);
+        assert_eq!(t.subtasks, todo.subtasks);
+        assert_eq!(t.metadata.priority, todo.metadata.priority);
+        assert_eq!(t.metadata.due_date, todo.metadata.due_date);
+        assert_eq!(t.metadata.recurrence, todo.metadata.recurrence);
+        assert_eq!(
+            t.metadata.added_at.date_naive(),
+            todo.metadata.added_at.date_naive()
+        );
+        assert_eq!(imported[1].name, other.name);
+        // imported todos only get their id once they are added to a list
+        let mut again = imported.clone();
+        again[0].id = todo.id;
+        assert_eq!(export(&again), out);
+    }
+
+    #[test]
+    fn keeps_keys_that_link_nothing() {
+        let todos = import("call bob p:555\nid:7 water plants\nrepot p:7").unwrap();
+        let names: Vec<&str> = todos.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, ["call bob p:555", "water plants"]);
+        assert_eq!(todos[1].subtasks, [Subtask::new("repot".into())]);
+    }
+}
@@ -0,0 +1,146 @@
+use crate::settings::ToodSettings;
+use crate::store::{self, TodoListSerde};
+use anyhow::{anyhow, bail, Result};
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+// only the lists are tracked, settings and credentials stay out of the history
+static GITIGNORE: &str = "*\n!.gitignore\n!todos.ron\n!todos-*.ron\n";
+
+/// The data directory as a git repository with a commit for every change to the lists.
+pub struct Repo {
+    dir: PathBuf,
+}
+
+pub struct Commit {
+    pub hash: String,
+    pub date: String,
+    pub message: String,
+}
+
+/// Commits the lists if git history is turned on.
+pub fn record(settings: &ToodSettings, message: &str) -> Result<()> {
+    match Repo::open(settings)? {
+        Some(repo) => repo.commit(message),
+        None => Ok(()),
+    }
+}
+
+/// Explains why changes to `list` are left out of the history although it is turned on.
+/// Only the data directory is tracked, not list files kept elsewhere.
+pub fn untracked(settings: &ToodSettings, list: &str) -> Result<Option<String>> {
+    if !settings.git_history {
+        return Ok(None);
+    }
+    untracked_reason(list)
+}
+
+fn untracked_reason(list: &str) -> Result<Option<String>> {
+    if store::in_data_dir(list)? {
+        return Ok(None);
+    }
+    Ok(Some(format!(
+        "{} is not kept in the git history, which only covers {}",
+        store::file_path(list)?.display(),
+        store::data_dir()?.display()
+    )))
+}
+
+impl Repo {
+    /// Opens the repository if git history is turned on, creating it on first use.
+    pub fn open(settings: &ToodSettings) -> Result<Option<Self>> {
+        if !settings.git_history {
+            return Ok(None);
+        }
+        let repo = Self {
+            dir: store::data_dir()?,
+        };
+        if !repo.dir.join(".git").exists() {
+            repo.init()?;
+        }
+        Ok(Some(repo))
+    }
+
+    fn init(&self) -> Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        self.git(&["init", "-q"])?;
+        fs::write(self.dir.join(".gitignore"), GITIGNORE)?;
+        // committing must not depend on the user having set up git
+        if self.git(&["config", "user.email"]).is_err() {
+            self.git(&["config", "user.name", "tood"])?;
+            self.git(&["config", "user.email", "tood@localhost"])?;
+        }
+        self.commit("Start tracking todos")
+    }
+
+    fn git(&self, args: &[&str]) -> Result<String> {
+        let out = Command::new("git")
+            .arg("-C")
+            .arg(&self.dir)
+            .args(args)
+            .output()
+            .map_err(|e| anyhow!("failed to run git: {e}"))?;
+        if !out.status.success() {
+            bail!(
+                "git {} failed: {}",
+                args[0],
+                String::from_utf8_lossy(&out.stderr).trim()
+            );
+        }
+        Ok(String::from_utf8_lossy(&out.stdout).into_owned())
+    }
+
+    /// Commits the current state of all lists, unless nothing changed.
+    pub fn commit(&self, message: &str) -> Result<()> {
+        self.git(&["add", "-A"])?;
+        if self.git(&["diff", "--cached", "--quiet"]).is_ok() {
+            return Ok(());
+        }
+        self.git(&["commit", "-q", "-m", message])?;
+        Ok(())
+    }
+
+    /// The latest commits that changed `list`, newest first.
+    pub fn log(&self, list: &str, limit: usize) -> Result<Vec<Commit>> {
+        if let Some(reason) = untracked_reason(list)? {
+            bail!(reason);
+        }
+        if self.git(&["rev-parse", "-q", "--verify", "HEAD"]).is_err() {
+            return Ok(Vec::new());
+        }
+        let out = self.git(&[
+            "log",
+            &format!("--max-count={limit}"),
+            "--format=%h%x09%ad%x09%s",
+            "--date=format:%Y-%m-%d %H:%M",
+            "--",
+            &store::file_name_on_disk(list),
+        ])?;
+        Ok(out
+            .lines()
+            .filter_map(|l| {
+                let mut parts = l.splitn(3, '\t');
+                Some(Commit {
+                    hash: parts.next()?.to_string(),
+                    date: parts.next()?.to_string(),
+                    message: parts.next()?.to_string(),
+                })
+            })
+            .collect())
+    }
+
+    /// The list as it was stored in the given commit.
+    pub fn list_at(&self, rev: &str, list: &str) -> Result<TodoListSerde> {
+        if let Some(reason) = untracked_reason(list)? {
+            bail!(reason);
+        }
+        self.git(&["rev-parse", "-q", "--verify", &format!("{rev}^{{commit}}")])
+            .map_err(|_| anyhow!("no commit {rev}"))?;
+        let file = store::file_name_on_disk(list);
+        let content = self
+            .git(&["show", &format!("{rev}:{file}")])
+            .map_err(|_| anyhow!("list {list} did not exist in commit {rev}"))?;
+        TodoListSerde::from_ron(&content)
+    }
+}
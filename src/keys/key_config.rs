@@ -16,16 +16,35 @@ pub struct KeyConfig {
     pub alt_move_right: Option<Keybind>,
     pub toggle_completed: Option<Keybind>,
     pub add_todo: Option<Keybind>,
+    pub add_subtask: Option<Keybind>,
+    pub indent_subtask: Option<Keybind>,
+    pub outdent_subtask: Option<Keybind>,
     pub external_editor: Option<Keybind>,
     pub edit_todo: Option<Keybind>,
     pub open_calendar: Option<Keybind>,
+    pub next_year: Option<Keybind>,
+    pub prev_year: Option<Keybind>,
+    pub go_to_today: Option<Keybind>,
+    pub calendar_view: Option<Keybind>,
+    pub agenda_view: Option<Keybind>,
     pub remove_todo: Option<Keybind>,
     pub mark_recurring: Option<Keybind>,
+    pub priority_up: Option<Keybind>,
+    pub priority_down: Option<Keybind>,
+    pub cycle_priority: Option<Keybind>,
     pub desc_scroll_up: Option<Keybind>,
     pub desc_scroll_down: Option<Keybind>,
     pub submit: Option<Keybind>,
     pub find_mode: Option<Keybind>,
+    pub filter_tags: Option<Keybind>,
     pub move_mode: Option<Keybind>,
+    pub sort_mode: Option<Keybind>,
+    pub group_mode: Option<Keybind>,
+    pub undo: Option<Keybind>,
+    pub redo: Option<Keybind>,
+    pub switch_list: Option<Keybind>,
+    pub move_to_list: Option<Keybind>,
+    pub sync: Option<Keybind>,
     pub back: Option<Keybind>,
     pub quit: Option<Keybind>,
 }
@@ -48,16 +67,35 @@ impl Config for KeyConfig {
             alt_move_right:   self.alt_move_right.unwrap_or(dkl.alt_move_right),
             toggle_completed: self.toggle_completed.unwrap_or(dkl.toggle_completed),
             add_todo:         self.add_todo.unwrap_or(dkl.add_todo),
+            add_subtask:      self.add_subtask.unwrap_or(dkl.add_subtask),
+            indent_subtask:   self.indent_subtask.unwrap_or(dkl.indent_subtask),
+            outdent_subtask:  self.outdent_subtask.unwrap_or(dkl.outdent_subtask),
             external_editor:  self.external_editor.unwrap_or(dkl.external_editor),
             edit_todo:        self.edit_todo.unwrap_or(dkl.edit_todo),
             open_calendar:    self.open_calendar.unwrap_or(dkl.open_calendar),
+            next_year:        self.next_year.unwrap_or(dkl.next_year),
+            prev_year:        self.prev_year.unwrap_or(dkl.prev_year),
+            go_to_today:      self.go_to_today.unwrap_or(dkl.go_to_today),
+            calendar_view:    self.calendar_view.unwrap_or(dkl.calendar_view),
+            agenda_view:      self.agenda_view.unwrap_or(dkl.agenda_view),
             remove_todo:      self.remove_todo.unwrap_or(dkl.remove_todo),
             mark_recurring:   self.mark_recurring.unwrap_or(dkl.mark_recurring),
+            priority_up:      self.priority_up.unwrap_or(dkl.priority_up),
+            priority_down:    self.priority_down.unwrap_or(dkl.priority_down),
+            cycle_priority:   self.cycle_priority.unwrap_or(dkl.cycle_priority),
             desc_scroll_up:   self.desc_scroll_up.unwrap_or(dkl.desc_scroll_up),
             desc_scroll_down: self.desc_scroll_down.unwrap_or(dkl.desc_scroll_down),
             submit:           self.submit.unwrap_or(dkl.submit),
             find_mode:        self.find_mode.unwrap_or(dkl.find_mode),
+            filter_tags:      self.filter_tags.unwrap_or(dkl.filter_tags),
             move_mode:        self.move_mode.unwrap_or(dkl.move_mode),
+            sort_mode:        self.sort_mode.unwrap_or(dkl.sort_mode),
+            group_mode:       self.group_mode.unwrap_or(dkl.group_mode),
+            undo:             self.undo.unwrap_or(dkl.undo),
+            redo:             self.redo.unwrap_or(dkl.redo),
+            switch_list:      self.switch_list.unwrap_or(dkl.switch_list),
+            move_to_list:     self.move_to_list.unwrap_or(dkl.move_to_list),
+            sync:             self.sync.unwrap_or(dkl.sync),
             back:             self.back.unwrap_or(dkl.back),
             quit:             self.quit.unwrap_or(dkl.quit),
         };
@@ -21,16 +21,35 @@ pub struct ToodKeyList {
     pub alt_move_right: Keybind,
     pub toggle_completed: Keybind,
     pub add_todo: Keybind,
+    pub add_subtask: Keybind,
+    pub indent_subtask: Keybind,
+    pub outdent_subtask: Keybind,
     pub external_editor: Keybind,
     pub edit_todo: Keybind,
     pub open_calendar: Keybind,
+    pub next_year: Keybind,
+    pub prev_year: Keybind,
+    pub go_to_today: Keybind,
+    pub calendar_view: Keybind,
+    pub agenda_view: Keybind,
     pub remove_todo: Keybind,
     pub mark_recurring: Keybind,
+    pub priority_up: Keybind,
+    pub priority_down: Keybind,
+    pub cycle_priority: Keybind,
     pub desc_scroll_up: Keybind,
     pub desc_scroll_down: Keybind,
     pub submit: Keybind,
     pub find_mode: Keybind,
+    pub filter_tags: Keybind,
     pub move_mode: Keybind,
+    pub sort_mode: Keybind,
+    pub group_mode: Keybind,
+    pub undo: Keybind,
+    pub redo: Keybind,
+    pub switch_list: Keybind,
+    pub move_to_list: Keybind,
+    pub sync: Keybind,
     pub back: Keybind,
     pub quit: Keybind,
 }
@@ -49,16 +68,35 @@ impl Default for ToodKeyList {
             alt_move_right:      Keybind::new(KeyCode::Char('L'), KeyModifiers::SHIFT),
             toggle_completed:    Keybind::new(KeyCode::Char(' '), KeyModifiers::empty()),
             add_todo:            Keybind::new(KeyCode::Char('a'), KeyModifiers::empty()),
+            add_subtask:         Keybind::new(KeyCode::Char('A'), KeyModifiers::SHIFT),
+            indent_subtask:      Keybind::new(KeyCode::Char('>'), KeyModifiers::empty()),
+            outdent_subtask:     Keybind::new(KeyCode::Char('<'), KeyModifiers::empty()),
             external_editor:     Keybind::new(KeyCode::Char('e'), KeyModifiers::CONTROL),
             edit_todo:           Keybind::new(KeyCode::Char('e'), KeyModifiers::empty()),
             open_calendar:       Keybind::new(KeyCode::Char('d'), KeyModifiers::CONTROL),
+            next_year:           Keybind::new(KeyCode::Char('J'), KeyModifiers::SHIFT),
+            prev_year:           Keybind::new(KeyCode::Char('K'), KeyModifiers::SHIFT),
+            go_to_today:         Keybind::new(KeyCode::Char('t'), KeyModifiers::empty()),
+            calendar_view:       Keybind::new(KeyCode::Char('c'), KeyModifiers::empty()),
+            agenda_view:         Keybind::new(KeyCode::Char('w'), KeyModifiers::empty()),
             remove_todo:         Keybind::new(KeyCode::Char('d'), KeyModifiers::empty()),
             mark_recurring:      Keybind::new(KeyCode::Char('r'), KeyModifiers::CONTROL),
+            priority_up:         Keybind::new(KeyCode::Char('+'), KeyModifiers::empty()),
+            priority_down:       Keybind::new(KeyCode::Char('-'), KeyModifiers::empty()),
+            cycle_priority:      Keybind::new(KeyCode::Char('p'), KeyModifiers::CONTROL),
             desc_scroll_up:      Keybind::new(KeyCode::Up,        KeyModifiers::CONTROL),
             desc_scroll_down:    Keybind::new(KeyCode::Down,      KeyModifiers::CONTROL),
             submit:              Keybind::new(KeyCode::Enter,     KeyModifiers::empty()),
             find_mode:           Keybind::new(KeyCode::Char('f'), KeyModifiers::empty()),
+            filter_tags:         Keybind::new(KeyCode::Char('t'), KeyModifiers::empty()),
             move_mode:           Keybind::new(KeyCode::Char('m'), KeyModifiers::empty()),
+            sort_mode:           Keybind::new(KeyCode::Char('s'), KeyModifiers::empty()),
+            group_mode:          Keybind::new(KeyCode::Char('g'), KeyModifiers::empty()),
+            undo:                Keybind::new(KeyCode::Char('u'), KeyModifiers::empty()),
+            redo:                Keybind::new(KeyCode::Char('r'), KeyModifiers::CONTROL),
+            switch_list:         Keybind::new(KeyCode::Char('o'), KeyModifiers::empty()),
+            move_to_list:        Keybind::new(KeyCode::Char('O'), KeyModifiers::SHIFT),
+            sync:                Keybind::new(KeyCode::Char('S'), KeyModifiers::SHIFT),
             back:                Keybind::new(KeyCode::Esc,       KeyModifiers::empty()),
             quit:                Keybind::new(KeyCode::Char('q'), KeyModifiers::empty()),
         }
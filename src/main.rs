@@ -1,14 +1,33 @@
+mod agenda;
 mod app;
+mod cli;
 mod components;
 #[macro_use]
 mod config;
+mod due;
+mod filter;
+mod formats;
+mod git;
 mod keys;
+mod merge;
+mod priority;
+mod quick_add;
+mod recurrence;
+mod remind;
+mod settings;
+mod store;
+mod subtask;
+mod sync;
+mod tags;
 mod theme;
 mod ui;
+mod view;
 mod widgets;
 
+use anyhow::anyhow;
 use app::App;
-use std::{error::Error, time::Duration};
+use cli::{Cli, Command};
+use std::{env, error::Error, process, time::Duration};
 
 #[cfg(feature = "dhat-heap")]
 #[global_allocator]
@@ -20,7 +39,32 @@ fn main() -> Result<(), Box<dyn Error>> {
     #[cfg(feature = "dhat-heap")]
     let _profiler = dhat::Profiler::new_heap();
 
-    let app = App::new();
+    let cli = match Cli::parse(env::args().skip(1)) {
+        Ok(cli) => cli,
+        Err(e) => exit_with_error(e),
+    };
+    if let Some(path) = store::locate_default_file(cli.file.clone()) {
+        store::use_default_file(path);
+    }
+
+    if !matches!(cli.command, Command::Tui) {
+        if let Err(e) = cli::run(&cli.list, cli.command) {
+            exit_with_error(e);
+        }
+        return Ok(());
+    }
+
+    match store::list_exists(&cli.list) {
+        Ok(true) => {}
+        Ok(false) => exit_with_error(anyhow!(
+            "no list named '{}', create it with `tood lists new {}`",
+            cli.list,
+            cli.list
+        )),
+        Err(e) => exit_with_error(e),
+    }
+
+    let app = App::new(cli.list);
 
     let res = ui::run(app);
 
@@ -30,3 +74,8 @@ fn main() -> Result<(), Box<dyn Error>> {
 
     Ok(())
 }
+
+fn exit_with_error(e: anyhow::Error) -> ! {
+    eprintln!("tood: {e}");
+    process::exit(1);
+}
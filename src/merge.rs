@@ -0,0 +1,196 @@
+use crate::components::todo_list::{Todo, TodoId};
+use crate::store::{TodoListSerde, SCHEMA_VERSION};
+use std::collections::{HashMap, HashSet};
+
+/// Which version to keep of todos changed both locally and on disk.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Prefer {
+    Local,
+    Disk,
+}
+
+pub struct Merged {
+    pub data: TodoListSerde,
+    /// Todos that were changed, or changed and removed, on both sides.
+    pub conflicts: Vec<TodoId>,
+}
+
+/// Merges the changes made locally and on disk since both sides had the `base` todos.
+/// Changes made on only one side are kept, conflicting ones are taken from the `prefer` side.
+/// The order on disk wins, todos added locally are appended and get a new id if theirs is taken.
+pub fn merge(base: &[Todo], local: &TodoListSerde, disk: &TodoListSerde, prefer: Prefer) -> Merged {
+    let by_id = |todos: &[Todo]| -> HashMap<TodoId, usize> {
+        todos.iter().enumerate().map(|(i, t)| (t.id, i)).collect()
+    };
+    let (base_ids, local_ids) = (by_id(base), by_id(&local.todos));
+    let base_of = |id| base_ids.get(&id).map(|i| &base[*i]);
+    let local_of = |id| local_ids.get(&id).map(|i| &local.todos[*i]);
+
+    let mut todos = Vec::with_capacity(disk.todos.len());
+    let mut added = Vec::new();
+    let mut conflicts = Vec::new();
+
+    for d in disk.todos.iter() {
+        match (base_of(d.id), local_of(d.id)) {
+            (Some(b), Some(l)) => {
+                let (local_changed, disk_changed) = (l != b, d != b);
+                if local_changed && disk_changed && l != d {
+                    conflicts.push(d.id);
+                    todos.push(if prefer == Prefer::Local { l } else { d }.clone());
+                } else if local_changed {
+                    todos.push(l.clone());
+                } else {
+                    todos.push(d.clone());
+                }
+            }
+            // removed locally
+            (Some(b), None) => {
+                if d != b {
+                    conflicts.push(d.id);
+                    if prefer == Prefer::Disk {
+                        todos.push(d.clone());
+                    }
+                }
+            }
+            // both sides added a todo and handed out the same id
+            (None, Some(l)) => {
+                todos.push(d.clone());
+                if l != d {
+                    added.push(l.clone());
+                }
+            }
+            (None, None) => todos.push(d.clone()),
+        }
+    }
+
+    let disk_ids: HashSet<TodoId> = disk.todos.iter().map(|t| t.id).collect();
+    for l in local.todos.iter().filter(|t| !disk_ids.contains(&t.id)) {
+        match base_of(l.id) {
+            // removed on disk
+            Some(b) => {
+                if l != b {
+                    conflicts.push(l.id);
+                    if prefer == Prefer::Local {
+                        todos.push(l.clone());
+                    }
+                }
+            }
+            None => added.push(l.clone()),
+        }
+    }
+
+    let max_id = todos.iter().map(|t| t.id).max().unwrap_or(0);
+    let mut next_id = local.next_id.max(disk.next_id).max(max_id + 1);
+    let mut taken: HashSet<TodoId> = todos.iter().map(|t| t.id).collect();
+    for mut t in added {
+        if !taken.insert(t.id) {
+            t.id = next_id;
+            next_id += 1;
+            taken.insert(t.id);
+        }
+        todos.push(t);
+    }
+    Merged {
+        data: TodoListSerde {
+            version: SCHEMA_VERSION,
+            next_id,
+            todos,
+        },
+        conflicts,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn todo(id: TodoId, name: &str) -> Todo {
+        Todo {
+            id,
+            name: name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn renamed(t: &Todo, name: &str) -> Todo {
+        Todo {
+            name: name.to_string(),
+            ..t.clone()
+        }
+    }
+
+    fn list(todos: Vec<Todo>) -> TodoListSerde {
+        TodoListSerde {
+            version: SCHEMA_VERSION,
+            next_id: todos.iter().map(|t| t.id + 1).max().unwrap_or(1),
+            todos,
+        }
+    }
+
+    fn names(merged: &Merged) -> Vec<(TodoId, &str)> {
+        merged
+            .data
+            .todos
+            .iter()
+            .map(|t| (t.id, t.name.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn keeps_changes_made_on_one_side() {
+        let base = vec![todo(1, "a"), todo(2, "b"), todo(3, "c")];
+        let local = list(vec![renamed(&base[0], "a local"), base[1].clone()]);
+        let disk = list(vec![
+            base[0].clone(),
+            renamed(&base[1], "b disk"),
+            base[2].clone(),
+        ]);
+
+        let merged = merge(&base, &local, &disk, Prefer::Local);
+        assert_eq!(names(&merged), [(1, "a local"), (2, "b disk")]);
+        assert!(merged.conflicts.is_empty());
+    }
+
+    #[test]
+    fn picks_a_side_for_todos_changed_on_both() {
+        let base = vec![todo(1, "a")];
+        let local = list(vec![renamed(&base[0], "local")]);
+        let disk = list(vec![renamed(&base[0], "disk")]);
+
+        let merged = merge(&base, &local, &disk, Prefer::Local);
+        assert_eq!(names(&merged), [(1, "local")]);
+        assert_eq!(merged.conflicts, [1]);
+
+        let merged = merge(&base, &local, &disk, Prefer::Disk);
+        assert_eq!(names(&merged), [(1, "disk")]);
+        assert_eq!(merged.conflicts, [1]);
+    }
+
+    #[test]
+    fn removing_and_editing_the_same_todo_conflicts() {
+        let base = vec![todo(1, "a"), todo(2, "b")];
+        // 1 is removed locally and edited on disk, 2 the other way around
+        let local = list(vec![renamed(&base[1], "b local")]);
+        let disk = list(vec![renamed(&base[0], "a disk")]);
+
+        let merged = merge(&base, &local, &disk, Prefer::Local);
+        assert_eq!(names(&merged), [(2, "b local")]);
+        assert_eq!(merged.conflicts, [1, 2]);
+
+        let merged = merge(&base, &local, &disk, Prefer::Disk);
+        assert_eq!(names(&merged), [(1, "a disk")]);
+        assert_eq!(merged.conflicts, [1, 2]);
+    }
+
+    #[test]
+    fn renumbers_todos_added_on_both_sides_with_the_same_id() {
+        let base = vec![todo(1, "a")];
+        let local = list(vec![base[0].clone(), todo(2, "local")]);
+        let disk = list(vec![base[0].clone(), todo(2, "disk")]);
+
+        let merged = merge(&base, &local, &disk, Prefer::Disk);
+        assert_eq!(names(&merged), [(1, "a"), (2, "disk"), (3, "local")]);
+        assert_eq!(merged.data.next_id, 4);
+        assert!(merged.conflicts.is_empty());
+    }
+}
@@ -0,0 +1,70 @@
+use anyhow::{bail, Error, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Priority {
+    #[default]
+    None,
+    Low,
+    Medium,
+    High,
+    Urgent,
+}
+
+impl Priority {
+    pub fn raise(self) -> Self {
+        match self {
+            Self::None => Self::Low,
+            Self::Low => Self::Medium,
+            Self::Medium => Self::High,
+            Self::High | Self::Urgent => Self::Urgent,
+        }
+    }
+
+    pub fn lower(self) -> Self {
+        match self {
+            Self::None | Self::Low => Self::None,
+            Self::Medium => Self::Low,
+            Self::High => Self::Medium,
+            Self::Urgent => Self::High,
+        }
+    }
+
+    /// Steps through every level, going back to `None` after `Urgent`.
+    pub fn cycle(self) -> Self {
+        match self {
+            Self::Urgent => Self::None,
+            p => p.raise(),
+        }
+    }
+}
+
+impl fmt::Display for Priority {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::None => "none",
+            Self::Low => "low",
+            Self::Medium => "medium",
+            Self::High => "high",
+            Self::Urgent => "urgent",
+        };
+        write!(f, "{name}")
+    }
+}
+
+impl FromStr for Priority {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(match s.trim().to_lowercase().as_str() {
+            "none" => Self::None,
+            "low" => Self::Low,
+            "medium" | "med" => Self::Medium,
+            "high" => Self::High,
+            "urgent" => Self::Urgent,
+            _ => bail!("unknown priority '{s}', expected none, low, medium, high or urgent"),
+        })
+    }
+}
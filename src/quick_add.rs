@@ -0,0 +1,167 @@
+use crate::due;
+use crate::priority::Priority;
+use crate::tags;
+use chrono::NaiveDateTime;
+
+// how far `due:` looks for the rest of its date, e.g. `due:tomorrow at 9 am`
+const MAX_DUE_WORDS: usize = 4;
+
+/// What a part of a quick-add line was recognised as.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Token {
+    Text,
+    Tag,
+    Priority,
+    Due,
+}
+
+/// A todo written on one line, like `Fix login bug #backend !high due:fri`.
+#[derive(Debug, Default, PartialEq)]
+pub struct QuickAdd {
+    pub name: String,
+    pub tags: Vec<String>,
+    pub priority: Option<Priority>,
+    pub due: Option<NaiveDateTime>,
+}
+
+/// Splits the line into what each part was recognised as, keeping the whitespace in between.
+pub fn tokenize(line: &str, now: NaiveDateTime) -> Vec<(&str, Token)> {
+    let words = words(line);
+    let mut tokens = Vec::new();
+    let (mut i, mut end) = (0, 0);
+    while i < words.len() {
+        let start = words[i].0;
+        let (token, last) = classify(line, &words, i, now);
+        if start > end {
+            tokens.push((&line[end..start], Token::Text));
+        }
+        end = words[last].1;
+        tokens.push((&line[start..end], token));
+        i = last + 1;
+    }
+    if end < line.len() {
+        tokens.push((&line[end..], Token::Text));
+    }
+    tokens
+}
+
+pub fn parse(line: &str, now: NaiveDateTime) -> QuickAdd {
+    let mut quick = QuickAdd::default();
+    let mut name = Vec::new();
+    for (s, token) in tokenize(line, now) {
+        match token {
+            Token::Text if !s.trim().is_empty() => name.push(s),
+            Token::Text => {}
+            Token::Tag => {
+                for tag in tags::parse(s) {
+                    if !quick.tags.contains(&tag) {
+                        quick.tags.push(tag);
+                    }
+                }
+            }
+            Token::Priority => quick.priority = s[1..].parse().ok(),
+            Token::Due => quick.due = due::parse(&s[4..], now).ok(),
+        }
+    }
+    quick.name = name.join(" ");
+    quick
+}
+
+/// The start and end of each word in the line.
+fn words(line: &str) -> Vec<(usize, usize)> {
+    let mut words = Vec::new();
+    let mut start = None;
+    for (i, c) in line.char_indices() {
+        match (c.is_whitespace(), start) {
+            (true, Some(s)) => {
+                words.push((s, i));
+                start = None;
+            }
+            (false, None) => start = Some(i),
+            _ => {}
+        }
+    }
+    if let Some(s) = start {
+        words.push((s, line.len()));
+    }
+    words
+}
+
+/// Recognises the token starting at word `i`, also returning the index of its last word.
+fn classify(line: &str, words: &[(usize, usize)], i: usize, now: NaiveDateTime) -> (Token, usize) {
+    let word = &line[words[i].0..words[i].1];
+    // `#42` is more likely an issue number than a tag
+    if let Some(tag) = word.strip_prefix('#') {
+        if tag.chars().any(|c| !c.is_ascii_digit() && c != '#') {
+            return (Token::Tag, i);
+        }
+    }
+    if let Some(priority) = word.strip_prefix('!') {
+        if priority.parse::<Priority>().is_ok() {
+            return (Token::Priority, i);
+        }
+    }
+    if word.len() > 4 && word.starts_with("due:") {
+        // the longest run of words that still reads as a date
+        for last in (i..words.len().min(i + MAX_DUE_WORDS)).rev() {
+            if due::parse(&line[words[i].0 + 4..words[last].1], now).is_ok() {
+                return (Token::Due, last);
+            }
+        }
+    }
+    (Token::Text, i)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn now() -> NaiveDateTime {
+        NaiveDateTime::parse_from_str("2026-10-14 10:00", "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    #[test]
+    fn splits_the_line() {
+        let quick = parse("Fix login bug #backend !high due:fri", now());
+        assert_eq!(quick.name, "Fix login bug");
+        assert_eq!(quick.tags, ["backend"]);
+        assert_eq!(quick.priority, Some(Priority::High));
+        assert_eq!(
+            quick.due.map(|d| d.to_string()),
+            Some("2026-10-16 23:59:00".into())
+        );
+    }
+
+    #[test]
+    fn due_dates_span_words() {
+        let quick = parse("call bob due:tomorrow 9am about #work", now());
+        assert_eq!(quick.name, "call bob about");
+        assert_eq!(
+            quick.due.map(|d| d.to_string()),
+            Some("2026-10-15 09:00:00".into())
+        );
+        assert_eq!(quick.tags, ["work"]);
+    }
+
+    #[test]
+    fn leaves_unrecognised_words_in_the_name() {
+        let quick = parse("close #42 !soon due:someday", now());
+        assert_eq!(quick.name, "close #42 !soon due:someday");
+        assert_eq!(
+            quick,
+            QuickAdd {
+                name: quick.name.clone(),
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn tokens_cover_the_line() {
+        let line = "  water plants  #home due:next sat ";
+        let tokens = tokenize(line, now());
+        assert_eq!(tokens.iter().map(|(s, _)| *s).collect::<String>(), line);
+        assert!(tokens.contains(&("due:next sat", Token::Due)));
+        assert!(tokens.contains(&("#home", Token::Tag)));
+    }
+}
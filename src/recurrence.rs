@@ -0,0 +1,228 @@
+use anyhow::{anyhow, bail, Error, Result};
+use chrono::{Datelike, Duration, Months, NaiveDate, NaiveDateTime, Weekday};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+static WEEKDAYS: [Weekday; 7] = [
+    Weekday::Mon,
+    Weekday::Tue,
+    Weekday::Wed,
+    Weekday::Thu,
+    Weekday::Fri,
+    Weekday::Sat,
+    Weekday::Sun,
+];
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum Recurrence {
+    Daily,
+    /// Repeats on the given weekdays, or every 7 days if no weekdays are given.
+    Weekly(Vec<Weekday>),
+    /// Repeats on the given day of the month, clamped to the last day of shorter months.
+    Monthly(u32),
+    EveryNDays(u32),
+}
+
+impl Recurrence {
+    /// Returns the first occurrence strictly after `dt`, keeping the time of day.
+    pub fn next_after(&self, dt: NaiveDateTime) -> NaiveDateTime {
+        match self {
+            Self::Daily => dt + Duration::days(1),
+            Self::EveryNDays(n) => dt + Duration::days(i64::from((*n).max(1))),
+            Self::Weekly(days) if days.is_empty() => dt + Duration::weeks(1),
+            Self::Weekly(days) => (1..=7)
+                .map(|n| dt + Duration::days(n))
+                .find(|d| days.contains(&d.weekday()))
+                .unwrap_or_else(|| dt + Duration::weeks(1)),
+            Self::Monthly(day) => {
+                let first = dt.date() - Duration::days(i64::from(dt.day0()));
+                day_in_month(first, *day)
+                    .filter(|d| *d > dt.date())
+                    .or_else(|| day_in_month(first.checked_add_months(Months::new(1))?, *day))
+                    .map(|d| NaiveDateTime::new(d, dt.time()))
+                    // there is no next month past the last date chrono can represent
+                    .unwrap_or(NaiveDateTime::MAX)
+            }
+        }
+    }
+
+    /// Walks the series starting at `from` until it passes `after`.
+    pub fn next_occurrence(&self, from: NaiveDateTime, after: NaiveDateTime) -> NaiveDateTime {
+        let mut next = from;
+        while next <= after {
+            next = self.next_after(next);
+        }
+        next
+    }
+}
+
+/// The number of days in the month starting on `first`.
+fn days_in_month(first: NaiveDate) -> u32 {
+    first
+        .checked_add_months(Months::new(1))
+        .and_then(|next| next.pred_opt())
+        // only the last month chrono can represent has no next one, and it is a December
+        .map_or(31, |last| last.day())
+}
+
+/// The given day in the month starting on `first`, clamped to the month's last day.
+fn day_in_month(first: NaiveDate, day: u32) -> Option<NaiveDate> {
+    first.with_day(day.clamp(1, days_in_month(first)))
+}
+
+fn weekday_name(d: &Weekday) -> &'static str {
+    match d {
+        Weekday::Mon => "mon",
+        Weekday::Tue => "tue",
+        Weekday::Wed => "wed",
+        Weekday::Thu => "thu",
+        Weekday::Fri => "fri",
+        Weekday::Sat => "sat",
+        Weekday::Sun => "sun",
+    }
+}
+
+fn parse_weekday(s: &str) -> Result<Weekday> {
+    WEEKDAYS
+        .iter()
+        .find(|d| s.starts_with(weekday_name(d)))
+        .copied()
+        .ok_or_else(|| anyhow!("unknown weekday '{s}'"))
+}
+
+impl fmt::Display for Recurrence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Daily => write!(f, "daily"),
+            Self::Weekly(days) if days.is_empty() => write!(f, "weekly"),
+            Self::Weekly(days) => {
+                let days: Vec<&str> = days.iter().map(weekday_name).collect();
+                write!(f, "weekly {}", days.join(","))
+            }
+            Self::Monthly(day) => write!(f, "monthly {day}"),
+            Self::EveryNDays(n) => write!(f, "every {n} days"),
+        }
+    }
+}
+
+/// Parses the same format `Display` writes, e.g. `daily`, `weekly mon,thu`,
+/// `monthly 15` or `every 3 days`.
+impl FromStr for Recurrence {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim().to_lowercase();
+        let mut words = s
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|w| !w.is_empty() && *w != "on");
+
+        let rule = match words.next() {
+            Some("daily") => Self::Daily,
+            Some("weekly") => {
+                let mut days = Vec::with_capacity(7);
+                for w in words.by_ref() {
+                    let d = parse_weekday(w)?;
+                    if !days.contains(&d) {
+                        days.push(d);
+                    }
+                }
+                days.sort_by_key(|d| d.num_days_from_monday());
+                Self::Weekly(days)
+            }
+            Some("monthly") => {
+                let day = match words.next() {
+                    Some(d) => d.parse()?,
+                    None => 1,
+                };
+                if !(1..=31).contains(&day) {
+                    bail!("day of month must be between 1 and 31");
+                }
+                Self::Monthly(day)
+            }
+            Some("every") => {
+                let n: u32 = words
+                    .next()
+                    .ok_or_else(|| anyhow!("missing day count"))?
+                    .trim_end_matches('d')
+                    .parse()?;
+                if n == 0 {
+                    bail!("day count must be at least 1");
+                }
+                if let Some(w) = words.next().filter(|w| !w.starts_with("day")) {
+                    bail!("unexpected '{w}' in recurrence");
+                }
+                Self::EveryNDays(n)
+            }
+            _ => bail!("expected daily, weekly <days>, monthly <day> or every <n> days"),
+        };
+
+        if let Some(w) = words.next() {
+            bail!("unexpected '{w}' in recurrence");
+        }
+        Ok(rule)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dt(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    #[test]
+    fn monthly_clamps_to_the_end_of_shorter_months() {
+        let rule = Recurrence::Monthly(31);
+        let feb = rule.next_after(dt("2026-01-31 09:00"));
+        assert_eq!(feb, dt("2026-02-28 09:00"));
+        assert_eq!(rule.next_after(feb), dt("2026-03-31 09:00"));
+        assert_eq!(
+            rule.next_after(dt("2026-12-31 09:00")),
+            dt("2027-01-31 09:00")
+        );
+        assert_eq!(
+            Recurrence::Monthly(15).next_after(dt("2026-01-10 09:00")),
+            dt("2026-01-15 09:00")
+        );
+    }
+
+    #[test]
+    fn weekly_wraps_past_sunday() {
+        let rule = Recurrence::Weekly(vec![Weekday::Mon, Weekday::Thu]);
+        // a thursday
+        let thu = dt("2026-10-15 18:00");
+        let mon = rule.next_after(thu);
+        assert_eq!(mon, dt("2026-10-19 18:00"));
+        assert_eq!(rule.next_after(mon), dt("2026-10-22 18:00"));
+        assert_eq!(
+            Recurrence::Weekly(Vec::new()).next_after(thu),
+            dt("2026-10-22 18:00")
+        );
+        assert_eq!(
+            rule.next_occurrence(thu, dt("2026-10-27 12:00")),
+            dt("2026-10-29 18:00")
+        );
+    }
+
+    #[test]
+    fn display_round_trips() {
+        let rules = [
+            Recurrence::Daily,
+            Recurrence::Weekly(Vec::new()),
+            Recurrence::Weekly(vec![Weekday::Mon, Weekday::Thu]),
+            Recurrence::Monthly(15),
+            Recurrence::EveryNDays(3),
+        ];
+        for rule in rules {
+            assert_eq!(rule.to_string().parse::<Recurrence>().unwrap(), rule);
+        }
+        assert_eq!(
+            "Weekly on Thursday, Mon".parse::<Recurrence>().unwrap(),
+            Recurrence::Weekly(vec![Weekday::Mon, Weekday::Thu])
+        );
+        assert!("monthly 32".parse::<Recurrence>().is_err());
+        assert!("every 0 days".parse::<Recurrence>().is_err());
+    }
+}
@@ -0,0 +1,190 @@
+use crate::components::todo_list::{Todo, TodoId, TIME_FORMAT};
+use crate::due;
+use crate::settings::ToodSettings;
+use crate::store::{self, TodoListSerde};
+use anyhow::{anyhow, bail, Error, Result};
+use chrono::{Duration, Local, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::fmt;
+use std::process;
+use std::str::FromStr;
+use std::thread;
+
+/// How long before its due date a todo should be reminded of, in minutes.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LeadTime(pub u32);
+
+impl LeadTime {
+    pub fn duration(&self) -> Duration {
+        Duration::minutes(i64::from(self.0))
+    }
+}
+
+impl fmt::Display for LeadTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            m if m >= 60 * 24 && m % (60 * 24) == 0 => write!(f, "{}d", m / (60 * 24)),
+            m if m >= 60 && m % 60 == 0 => write!(f, "{}h", m / 60),
+            m => write!(f, "{m}m"),
+        }
+    }
+}
+
+/// Parses lead times like `30m`, `2h` or `1d`. A plain number is a number of minutes.
+impl FromStr for LeadTime {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        let (num, unit) = s.split_at(s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len()));
+        let invalid = || anyhow!("invalid lead time '{s}', expected e.g. 30m, 2h or 1d");
+        let n: u32 = num.parse().map_err(|_| invalid())?;
+        let minutes = match unit {
+            "" | "m" => Some(n),
+            "h" => n.checked_mul(60),
+            "d" => n.checked_mul(60 * 24),
+            _ => bail!(invalid()),
+        };
+        Ok(Self(minutes.ok_or_else(invalid)?))
+    }
+}
+
+/// Reminders that were already sent, so each one only fires once.
+#[derive(Serialize, Deserialize, Default)]
+struct SentReminders {
+    // (list, todo id, the due date the reminder was sent for)
+    sent: Vec<(String, TodoId, NaiveDateTime)>,
+}
+
+struct Reminder {
+    list: String,
+    todo: Todo,
+    due: NaiveDateTime,
+}
+
+/// Sends a reminder for every todo in every list whose reminder time has come,
+/// and returns how many were sent.
+pub fn check(settings: &ToodSettings) -> Result<usize> {
+    let now = Local::now().naive_local();
+    let default_lead = LeadTime(settings.reminder_lead_minutes);
+    let mut state: SentReminders = confy::load("tood", Some("reminders"))?;
+
+    let mut due_todos = Vec::new();
+    let mut skipped = Vec::new();
+    for list in store::list_names()? {
+        if !store::file_path(&list)?.exists() {
+            continue;
+        }
+        // read only, an open list may be in the middle of being saved or migrated
+        let todos = match TodoListSerde::read(&list) {
+            Ok(todo_data) => todo_data.todos,
+            Err(e) => {
+                eprintln!("tood: skipping list {list}: {e}");
+                skipped.push(list);
+                continue;
+            }
+        };
+        for todo in todos {
+            let Some(due) = todo.metadata.due_date else {
+                continue;
+            };
+            if todo.metadata.finished && !todo.metadata.is_recurring() {
+                continue;
+            }
+            let lead = todo.metadata.remind_before.unwrap_or(default_lead);
+            // a lead time reaching past the earliest date means remind right away
+            let remind_at = due.checked_sub_signed(lead.duration());
+            if remind_at.is_none_or(|at| now >= at) {
+                due_todos.push(Reminder {
+                    list: list.clone(),
+                    todo,
+                    due,
+                });
+            }
+        }
+    }
+
+    // forget reminders for todos that were removed, finished or rescheduled
+    state.sent.retain(|(list, id, due)| {
+        skipped.contains(list)
+            || due_todos
+                .iter()
+                .any(|r| r.list == *list && r.todo.id == *id && r.due == *due)
+    });
+
+    let mut count = 0;
+    for r in due_todos {
+        let key = (r.list.clone(), r.todo.id, r.due);
+        if state.sent.contains(&key) {
+            continue;
+        }
+        send(settings, &r, now);
+        state.sent.push(key);
+        count += 1;
+    }
+
+    confy::store("tood", Some("reminders"), &state)?;
+    Ok(count)
+}
+
+/// Checks for reminders every `interval` until the process is killed.
+pub fn daemon(settings: &ToodSettings, interval: std::time::Duration) -> Result<()> {
+    loop {
+        if let Err(e) = check(settings) {
+            eprintln!("tood: failed to check reminders: {e}");
+        }
+        thread::sleep(interval);
+    }
+}
+
+fn send(settings: &ToodSettings, r: &Reminder, now: NaiveDateTime) {
+    let when = if r.due < now {
+        due::relative_label(r.due, now)
+    } else {
+        format!("due {}", due::relative_label(r.due, now))
+    };
+    println!("[{}] {} ({when})", r.list, r.todo.name);
+
+    if let Some(cmd) = &settings.reminder_command {
+        let status = process::Command::new("sh")
+            .arg("-c")
+            .arg(cmd)
+            .env("TOOD_LIST", &r.list)
+            .env("TOOD_ID", r.todo.id.to_string())
+            .env("TOOD_NAME", &r.todo.name)
+            .env("TOOD_DUE", r.due.format(TIME_FORMAT).to_string())
+            .status();
+        match status {
+            Ok(s) if !s.success() => eprintln!("tood: reminder command exited with {s}"),
+            Err(e) => eprintln!("tood: failed to run reminder command: {e}"),
+            Ok(_) => {}
+        }
+    }
+
+    // notify-send talks to the notification daemon over the session bus
+    if settings.desktop_notifications && env::var_os("DBUS_SESSION_BUS_ADDRESS").is_some() {
+        let res = process::Command::new("notify-send")
+            .arg("--app-name=tood")
+            .arg(&r.todo.name)
+            .arg(format!("{when} in {}", r.list))
+            .status();
+        if let Err(e) = res {
+            eprintln!("tood: failed to send desktop notification: {e}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_lead_times() {
+        assert_eq!("30".parse::<LeadTime>().unwrap(), LeadTime(30));
+        assert_eq!("2h".parse::<LeadTime>().unwrap(), LeadTime(120));
+        assert_eq!("1d".parse::<LeadTime>().unwrap(), LeadTime(60 * 24));
+        assert!("9999999d".parse::<LeadTime>().is_err());
+        assert!("99999999h".parse::<LeadTime>().is_err());
+    }
+}
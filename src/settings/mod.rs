@@ -0,0 +1,4 @@
+pub mod settings;
+pub mod settings_config;
+
+pub use settings::ToodSettings;
@@ -0,0 +1,60 @@
+use super::settings_config::SettingsConfig;
+use crate::{components::notification::FlashMsg, config::Config};
+use confy::ConfyError;
+use kanal::Sender;
+use serde::{Deserialize, Serialize};
+use std::rc::Rc;
+use tui_utils::shared::Shared;
+
+pub type SharedSettings = Rc<ToodSettings>;
+
+#[derive(Debug, Shared, Serialize, Deserialize)]
+pub struct ToodSettings {
+    /// Marks a todo (or subtask) as finished once all of its subtasks are finished.
+    pub complete_parent_with_subtasks: bool,
+    /// Todos due within this many hours are highlighted as due soon.
+    pub due_soon_hours: u32,
+    /// How many days ahead the agenda looks, counting today.
+    pub agenda_days: u32,
+    /// Default for how many minutes before their due date todos are reminded of.
+    pub reminder_lead_minutes: u32,
+    /// Shell command run for every reminder, with the todo in `TOOD_*` environment variables.
+    pub reminder_command: Option<String>,
+    pub desktop_notifications: bool,
+    /// Records every change to the lists as a commit in a git repository in the data directory.
+    pub git_history: bool,
+}
+
+impl Default for ToodSettings {
+    fn default() -> Self {
+        Self {
+            complete_parent_with_subtasks: true,
+            due_soon_hours: 48,
+            agenda_days: 7,
+            reminder_lead_minutes: 15,
+            reminder_command: None,
+            desktop_notifications: true,
+            git_history: false,
+        }
+    }
+}
+
+impl ToodSettings {
+    pub fn load() -> Result<SharedSettings, ConfyError> {
+        match SettingsConfig::read_from_file("settings")? {
+            Some(settings) => Ok(settings.to_shared()),
+            None => Ok(Self::shared()),
+        }
+    }
+
+    pub fn init(tx: Sender<FlashMsg>) -> SharedSettings {
+        match Self::load() {
+            Ok(settings) => settings,
+            Err(e) => {
+                tx.send(FlashMsg::err(format!("Failed to load settings: {e}")))
+                    .unwrap();
+                Self::shared()
+            }
+        }
+    }
+}
@@ -0,0 +1,36 @@
+use super::settings::ToodSettings;
+use crate::config::Config;
+use serde::{Deserialize, Serialize};
+use std::rc::Rc;
+
+#[derive(Deserialize, Serialize, Default, Debug)]
+pub struct SettingsConfig {
+    pub complete_parent_with_subtasks: Option<bool>,
+    pub due_soon_hours: Option<u32>,
+    pub agenda_days: Option<u32>,
+    pub reminder_lead_minutes: Option<u32>,
+    pub reminder_command: Option<String>,
+    pub desktop_notifications: Option<bool>,
+    pub git_history: Option<bool>,
+}
+
+impl Config for SettingsConfig {
+    type Item = ToodSettings;
+
+    fn to_shared(self) -> Rc<ToodSettings> {
+        let ds = ToodSettings::default();
+
+        #[rustfmt::skip]
+        let settings = ToodSettings {
+            complete_parent_with_subtasks: self.complete_parent_with_subtasks.unwrap_or(ds.complete_parent_with_subtasks),
+            due_soon_hours:                self.due_soon_hours.unwrap_or(ds.due_soon_hours),
+            agenda_days:                   self.agenda_days.unwrap_or(ds.agenda_days),
+            reminder_lead_minutes:         self.reminder_lead_minutes.unwrap_or(ds.reminder_lead_minutes),
+            reminder_command:              self.reminder_command.or(ds.reminder_command),
+            desktop_notifications:         self.desktop_notifications.unwrap_or(ds.desktop_notifications),
+            git_history:                   self.git_history.unwrap_or(ds.git_history),
+        };
+
+        Rc::new(settings)
+    }
+}
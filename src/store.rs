@@ -0,0 +1,566 @@
+use crate::components::todo_list::{Todo, TodoId};
+use crate::recurrence::Recurrence;
+use anyhow::{anyhow, bail, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::env;
+use std::ffi::OsString;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+/// The list stored in `todos.ron`, which is where todos lived before named lists existed.
+pub static DEFAULT_LIST: &str = "default";
+
+static LIST_FILE_PREFIX: &str = "todos-";
+
+/// Found in the current directory or at the root of its git repository, it takes the place
+/// of the default list so a project can carry its own todos.
+pub static PROJECT_FILE: &str = ".tood.ron";
+
+// where the default list lives when it is not in the data directory
+static DEFAULT_FILE: OnceLock<PathBuf> = OnceLock::new();
+
+/// Picks the file for the default list from the `--file` flag, the `TOOD_FILE`
+/// environment variable or a project file, in that order.
+pub fn locate_default_file(flag: Option<PathBuf>) -> Option<PathBuf> {
+    let path = flag
+        .or_else(|| {
+            env::var_os("TOOD_FILE")
+                .filter(|v| !v.is_empty())
+                .map(PathBuf::from)
+        })
+        .or_else(find_project_file)?;
+    // keep pointing at the same file if the working directory changes
+    match env::current_dir() {
+        Ok(cwd) => Some(cwd.join(path)),
+        Err(_) => Some(path),
+    }
+}
+
+fn find_project_file() -> Option<PathBuf> {
+    let cwd = env::current_dir().ok()?;
+    let here = cwd.join(PROJECT_FILE);
+    if here.is_file() {
+        return Some(here);
+    }
+    let root = cwd.ancestors().find(|dir| dir.join(".git").exists())?;
+    let file = root.join(PROJECT_FILE);
+    file.is_file().then_some(file)
+}
+
+/// Stores the default list in `path` instead of the data directory from now on.
+pub fn use_default_file(path: PathBuf) {
+    let _ = DEFAULT_FILE.set(path);
+}
+
+fn file_name(list: &str) -> String {
+    if list == DEFAULT_LIST {
+        "todos".into()
+    } else {
+        format!("{LIST_FILE_PREFIX}{list}")
+    }
+}
+
+/// The name of the file holding the list, relative to the data directory.
+pub fn file_name_on_disk(list: &str) -> String {
+    format!("{}.ron", file_name(list))
+}
+
+/// The file the list is stored in.
+pub fn file_path(list: &str) -> Result<PathBuf> {
+    if list == DEFAULT_LIST {
+        if let Some(path) = DEFAULT_FILE.get() {
+            return Ok(path.clone());
+        }
+    }
+    Ok(confy::get_configuration_file_path(
+        "tood",
+        Some(&*file_name(list)),
+    )?)
+}
+
+/// A file kept next to the list file, such as its backup.
+fn sidecar(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = OsString::from(path.as_os_str());
+    name.push(suffix);
+    name.into()
+}
+
+/// Whether the list is stored in the data directory, rather than in a file picked with
+/// `--file`, `TOOD_FILE` or a project file.
+pub fn in_data_dir(list: &str) -> Result<bool> {
+    Ok(file_path(list)? == data_dir()?.join(file_name_on_disk(list)))
+}
+
+/// The directory holding the lists and the configuration files.
+pub fn data_dir() -> Result<PathBuf> {
+    let path = confy::get_configuration_file_path("tood", Some(&*file_name(DEFAULT_LIST)))?;
+    path.parent()
+        .map(|p| p.to_path_buf())
+        .ok_or_else(|| anyhow!("{} has no parent directory", path.display()))
+}
+
+static LOCK_TIMEOUT: Duration = Duration::from_secs(5);
+// writes take milliseconds, so a lock this old was left behind by a crashed instance
+static STALE_LOCK: Duration = Duration::from_secs(30);
+
+/// Keeps other tood instances from writing the list while held.
+pub struct ListLock {
+    path: PathBuf,
+}
+
+impl ListLock {
+    /// Waits for other instances to release the list, giving up after a few seconds.
+    pub fn acquire(list: &str) -> Result<Self> {
+        let path = sidecar(&file_path(list)?, ".lock");
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let start = Instant::now();
+        loop {
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(mut file) => {
+                    // only informative, for whoever finds the file
+                    let _ = write!(file, "{}", std::process::id());
+                    return Ok(Self { path });
+                }
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {
+                    let age = fs::metadata(&path)
+                        .and_then(|m| m.modified())
+                        .ok()
+                        .and_then(|m| m.elapsed().ok());
+                    if age.is_some_and(|age| age > STALE_LOCK) {
+                        let _ = fs::remove_file(&path);
+                    } else if start.elapsed() > LOCK_TIMEOUT {
+                        bail!(
+                            "list {list} is locked by another tood, remove {} if none is running",
+                            path.display()
+                        );
+                    } else {
+                        thread::sleep(Duration::from_millis(20));
+                    }
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+}
+
+impl Drop for ListLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// When the list file was last written, `None` if it does not exist.
+pub fn modified(list: &str) -> Result<Option<SystemTime>> {
+    Ok(fs::metadata(file_path(list)?)
+        .and_then(|m| m.modified())
+        .ok())
+}
+
+fn backup_path(path: &Path) -> PathBuf {
+    sidecar(path, ".bak")
+}
+
+/// Replaces the file in one step, so a crash leaves either the old or the new
+/// contents behind. The old contents are kept as a backup.
+fn write_atomic(path: &Path, contents: &str) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let tmp = sidecar(path, ".tmp");
+    let mut file = File::create(&tmp)?;
+    file.write_all(contents.as_bytes())?;
+    file.sync_all()?;
+
+    if path.exists() {
+        fs::copy(path, backup_path(path))?;
+    }
+    fs::rename(&tmp, path)?;
+    // make the rename itself durable, not possible on every platform
+    if let Some(dir) = path.parent().and_then(|d| File::open(d).ok()) {
+        let _ = dir.sync_all();
+    }
+    Ok(())
+}
+
+pub fn validate_list_name(name: &str) -> Result<()> {
+    if name.is_empty() {
+        bail!("List name can't be empty");
+    }
+    if !name
+        .chars()
+        .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+    {
+        bail!("List names may only contain letters, digits, '-' and '_'");
+    }
+    Ok(())
+}
+
+pub fn list_exists(name: &str) -> Result<bool> {
+    Ok(name == DEFAULT_LIST || file_path(name)?.exists())
+}
+
+/// Returns the names of all lists, with the default list first.
+pub fn list_names() -> Result<Vec<String>> {
+    let mut names = Vec::new();
+    let dir = data_dir()?;
+    if dir.exists() {
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some("ron") {
+                continue;
+            }
+            if let Some(name) = path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .and_then(|s| s.strip_prefix(LIST_FILE_PREFIX))
+            {
+                names.push(name.to_string());
+            }
+        }
+    }
+    names.sort();
+    names.insert(0, DEFAULT_LIST.into());
+    Ok(names)
+}
+
+pub fn create_list(name: &str) -> Result<()> {
+    validate_list_name(name)?;
+    if list_exists(name)? {
+        bail!("List '{name}' already exists");
+    }
+    TodoListSerde::default().store(name)
+}
+
+pub fn rename_list(from: &str, to: &str) -> Result<()> {
+    validate_list_name(to)?;
+    if from == DEFAULT_LIST {
+        bail!("The default list can't be renamed");
+    }
+    if list_exists(to)? {
+        bail!("List '{to}' already exists");
+    }
+    let (from, to) = (file_path(from)?, file_path(to)?);
+    fs::rename(&from, &to)?;
+    if backup_path(&from).exists() {
+        fs::rename(backup_path(&from), backup_path(&to))?;
+    }
+    Ok(())
+}
+
+/// Moves the list file and its backup aside, returning where the todos went.
+pub fn delete_list(name: &str) -> Result<PathBuf> {
+    if name == DEFAULT_LIST {
+        bail!("The default list can't be deleted");
+    }
+    let path = file_path(name)?;
+    let deleted = sidecar(&path, ".deleted");
+    fs::rename(&path, &deleted)?;
+    if backup_path(&path).exists() {
+        fs::rename(backup_path(&path), backup_path(&deleted))?;
+    }
+    Ok(deleted)
+}
+
+/// The version of the list format this build writes. Changing the format means bumping it
+/// and adding the step from the previous version to `MIGRATIONS`.
+pub const SCHEMA_VERSION: u32 = 1;
+
+// `MIGRATIONS[v]` upgrades a list from version `v` to `v + 1`, files without a version are 0
+static MIGRATIONS: [fn(&mut TodoListSerde); SCHEMA_VERSION as usize] =
+    [TodoListSerde::upgrade_recurring];
+
+#[derive(Deserialize)]
+struct Header {
+    #[serde(default)]
+    version: u32,
+}
+
+/// The format version a list file was written in.
+fn file_version(input: &str) -> Result<u32> {
+    if input.trim().is_empty() {
+        return Ok(SCHEMA_VERSION);
+    }
+    Ok(ron::from_str::<Header>(input)?.version)
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct TodoListSerde {
+    #[serde(default)]
+    pub version: u32,
+    #[serde(default = "first_id")]
+    pub next_id: TodoId,
+    pub todos: Vec<Todo>,
+}
+
+fn first_id() -> TodoId {
+    1
+}
+
+impl Default for TodoListSerde {
+    fn default() -> Self {
+        Self {
+            version: SCHEMA_VERSION,
+            next_id: first_id(),
+            todos: Vec::new(),
+        }
+    }
+}
+
+impl TodoListSerde {
+    pub fn load(list: &str) -> Result<Self> {
+        Ok(Self::load_or_recover(list)?.0)
+    }
+
+    /// Loads the list, falling back to its backup if the file is damaged.
+    /// The damaged file is moved aside and a message describing the recovery is returned.
+    /// Files in an older format are copied aside before being migrated.
+    pub fn load_or_recover(list: &str) -> Result<(Self, Option<String>)> {
+        Self::load_or_recover_at(&file_path(list)?)
+    }
+
+    /// Like `load_or_recover`, for the list stored in `path`.
+    pub fn load_or_recover_at(path: &Path) -> Result<(Self, Option<String>)> {
+        if !path.exists() {
+            let todo_data = Self::default();
+            todo_data.store_at(path)?;
+            return Ok((todo_data, None));
+        }
+        let input = fs::read_to_string(path)?;
+        let err = match Self::parse(&input) {
+            Ok((todo_data, version)) if version < SCHEMA_VERSION => {
+                fs::copy(path, sidecar(path, &format!(".v{version}.bak")))?;
+                todo_data.store_at(path)?;
+                return Ok((todo_data, None));
+            }
+            Ok((todo_data, _)) => return Ok((todo_data, None)),
+            // not damaged, recovering would throw away todos this build cannot read
+            Err(e) if file_version(&input).is_ok_and(|v| v > SCHEMA_VERSION) => return Err(e),
+            Err(e) => e,
+        };
+
+        // otherwise the next save would turn the damaged file into the backup
+        let damaged = sidecar(path, ".damaged");
+        fs::rename(path, &damaged)?;
+        let backup = fs::read_to_string(backup_path(path))
+            .ok()
+            .and_then(|b| Self::from_ron(&b).ok());
+        let (todo_data, msg) = match backup {
+            Some(todo_data) => (todo_data, "loaded its backup"),
+            None => (Self::default(), "found no usable backup, starting empty"),
+        };
+        todo_data.store_at(path)?;
+        Ok((
+            todo_data,
+            Some(format!(
+                "{} is damaged ({err}), {msg}. The damaged file is {}",
+                path.display(),
+                damaged.display()
+            )),
+        ))
+    }
+
+    /// Loads the list without recovering from a damaged file, which could also be one
+    /// that is still being written.
+    pub fn read(list: &str) -> Result<Self> {
+        Self::from_ron(&fs::read_to_string(file_path(list)?)?)
+    }
+
+    /// Parses a list from the contents of its file, migrating it to the current format.
+    pub fn from_ron(input: &str) -> Result<Self> {
+        Ok(Self::parse(input)?.0)
+    }
+
+    /// Like `from_ron`, also returning the version the list was stored in.
+    fn parse(input: &str) -> Result<(Self, u32)> {
+        // a freshly created project file
+        if input.trim().is_empty() {
+            return Ok((Self::default(), SCHEMA_VERSION));
+        }
+        let version = file_version(input)?;
+        if version > SCHEMA_VERSION {
+            bail!(
+                "the list was written by a newer version of tood \
+                 (format {version}, this one reads up to {SCHEMA_VERSION})"
+            );
+        }
+        let mut todo_data: Self = ron::from_str(input)?;
+        for migrate in MIGRATIONS[version as usize..].iter() {
+            migrate(&mut todo_data);
+        }
+        todo_data.version = SCHEMA_VERSION;
+        todo_data.backfill_ids();
+        Ok((todo_data, version))
+    }
+
+    pub fn store(&self, list: &str) -> Result<()> {
+        self.store_at(&file_path(list)?)
+    }
+
+    pub fn store_at(&self, path: &Path) -> Result<()> {
+        let contents = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())?;
+        write_atomic(path, &contents)
+    }
+
+    /// Assigns ids to todos stored before ids existed and makes sure
+    /// `next_id` never hands out an id that is already in use.
+    fn backfill_ids(&mut self) {
+        let max_id = self.todos.iter().map(|t| t.id).max().unwrap_or(0);
+        self.next_id = self.next_id.max(max_id + 1);
+
+        let mut seen = HashSet::with_capacity(self.todos.len());
+        for t in self.todos.iter_mut() {
+            if t.id == 0 || !seen.insert(t.id) {
+                t.id = self.next_id;
+                self.next_id += 1;
+                seen.insert(t.id);
+            }
+        }
+    }
+
+    /// Gives todos that were only flagged as recurring a daily recurrence rule.
+    fn upgrade_recurring(&mut self) {
+        for t in self.todos.iter_mut() {
+            if t.metadata.legacy_recurring && t.metadata.recurrence.is_none() {
+                t.metadata.recurrence = Some(Recurrence::Daily);
+            }
+            t.metadata.legacy_recurring = false;
+        }
+    }
+
+    pub fn push(&mut self, mut t: Todo) -> TodoId {
+        t.id = self.next_id;
+        self.next_id += 1;
+        self.todos.push(t);
+        self.next_id - 1
+    }
+
+    pub fn get_mut(&mut self, id: TodoId) -> Option<&mut Todo> {
+        self.todos.iter_mut().find(|t| t.id == id)
+    }
+
+    /// Puts back a todo from an older version of the list, replacing its current state.
+    pub fn restore(&mut self, t: Todo) {
+        match self.get_mut(t.id) {
+            Some(current) => *current = t,
+            None => {
+                self.next_id = self.next_id.max(t.id + 1);
+                self.todos.push(t);
+            }
+        }
+    }
+
+    pub fn remove(&mut self, id: TodoId) -> Option<Todo> {
+        let i = self.todos.iter().position(|t| t.id == id)?;
+        Some(self.todos.remove(i))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::recurrence::Recurrence;
+
+    // every shape the list file ever had, add one whenever the format changes
+    fn corpus() -> Vec<(String, String)> {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/corpus");
+        let mut files = fs::read_dir(dir)
+            .unwrap()
+            .map(|e| e.unwrap().path())
+            .filter(|p| p.extension().is_some_and(|e| e == "ron"))
+            .map(|p| {
+                let name = p.file_name().unwrap().to_string_lossy().into_owned();
+                (name, fs::read_to_string(p).unwrap())
+            })
+            .collect::<Vec<_>>();
+        files.sort();
+        files
+    }
+
+    fn load(name: &str) -> TodoListSerde {
+        let (_, input) = corpus().into_iter().find(|(n, _)| n == name).unwrap();
+        TodoListSerde::from_ron(&input).unwrap()
+    }
+
+    #[test]
+    fn corpus_loads_and_round_trips() {
+        let files = corpus();
+        assert!(files.len() >= 4);
+        for (name, input) in files {
+            let data = TodoListSerde::from_ron(&input).unwrap_or_else(|e| panic!("{name}: {e}"));
+            assert_eq!(data.version, SCHEMA_VERSION, "{name}");
+            assert!(!data.todos.is_empty(), "{name}");
+
+            let ids = data.todos.iter().map(|t| t.id).collect::<HashSet<_>>();
+            assert_eq!(ids.len(), data.todos.len(), "{name}: duplicate ids");
+            assert!(!ids.contains(&0), "{name}: missing id");
+            assert!(
+                ids.iter().all(|id| *id < data.next_id),
+                "{name}: next_id in use"
+            );
+
+            let stored = ron::ser::to_string_pretty(&data, Default::default()).unwrap();
+            let reloaded = TodoListSerde::from_ron(&stored).unwrap();
+            assert_eq!(reloaded.todos, data.todos, "{name}");
+            assert_eq!(reloaded.next_id, data.next_id, "{name}");
+        }
+    }
+
+    #[test]
+    fn original_format() {
+        let data = load("v0-original.ron");
+        let ids = data.todos.iter().map(|t| t.id).collect::<Vec<_>>();
+        assert_eq!(ids, [1, 2, 3]);
+        assert_eq!(data.next_id, 4);
+        assert!(data.todos[0].metadata.finished);
+        assert_eq!(data.todos[1].description, "ten minutes\nbefore lunch");
+        assert_eq!(data.todos[1].metadata.recurrence, Some(Recurrence::Daily));
+        assert_eq!(data.todos[0].metadata.recurrence, None);
+        assert_eq!(data.todos[2].name, "");
+    }
+
+    #[test]
+    fn duplicate_ids_are_reassigned() {
+        let data = load("v0-with-ids.ron");
+        let ids = data.todos.iter().map(|t| t.id).collect::<Vec<_>>();
+        assert_eq!(ids, [1, 3, 4]);
+        assert_eq!(data.next_id, 5);
+        assert_eq!(data.todos[0].metadata.recurrence, Some(Recurrence::Daily));
+    }
+
+    #[test]
+    fn newer_format_is_refused() {
+        let input = "(version: 99, next_id: 1, todos: [])";
+        let err = TodoListSerde::from_ron(input).unwrap_err();
+        assert!(err.to_string().contains("newer version"), "{err}");
+    }
+
+    #[test]
+    fn migrating_keeps_a_copy_of_the_old_file() {
+        let dir = env::temp_dir().join(format!("tood-migrate-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("todos.ron");
+        let (_, original) = corpus()
+            .into_iter()
+            .find(|(n, _)| n == "v0-original.ron")
+            .unwrap();
+        fs::write(&path, &original).unwrap();
+
+        let (data, msg) = TodoListSerde::load_or_recover_at(&path).unwrap();
+        assert!(msg.is_none());
+        assert_eq!(
+            fs::read_to_string(sidecar(&path, ".v0.bak")).unwrap(),
+            original
+        );
+        let migrated = fs::read_to_string(&path).unwrap();
+        assert_eq!(file_version(&migrated).unwrap(), SCHEMA_VERSION);
+        assert_eq!(data.version, SCHEMA_VERSION);
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
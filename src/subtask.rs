@@ -0,0 +1,165 @@
+use serde::{Deserialize, Serialize};
+
+/// Indices leading from a todo's subtasks down through `children` to a single subtask.
+pub type SubtaskPath = Vec<usize>;
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Subtask {
+    pub name: String,
+    #[serde(default)]
+    pub finished: bool,
+    #[serde(default)]
+    pub children: Vec<Subtask>,
+}
+
+impl Subtask {
+    pub fn new(name: String) -> Self {
+        Self {
+            name,
+            ..Default::default()
+        }
+    }
+}
+
+pub fn get<'a>(items: &'a [Subtask], path: &[usize]) -> Option<&'a Subtask> {
+    let (first, rest) = path.split_first()?;
+    let item = items.get(*first)?;
+    if rest.is_empty() {
+        Some(item)
+    } else {
+        get(&item.children, rest)
+    }
+}
+
+pub fn get_mut<'a>(items: &'a mut [Subtask], path: &[usize]) -> Option<&'a mut Subtask> {
+    let (first, rest) = path.split_first()?;
+    let item = items.get_mut(*first)?;
+    if rest.is_empty() {
+        Some(item)
+    } else {
+        get_mut(&mut item.children, rest)
+    }
+}
+
+/// Returns the list the subtask at `path` lives in.
+fn siblings_mut<'a>(items: &'a mut Vec<Subtask>, path: &[usize]) -> Option<&'a mut Vec<Subtask>> {
+    match path.split_last()? {
+        (_, []) => Some(items),
+        (_, parent) => get_mut(items, parent).map(|p| &mut p.children),
+    }
+}
+
+/// Counts finished and total subtasks at every depth.
+pub fn progress(items: &[Subtask]) -> (usize, usize) {
+    items.iter().fold((0, 0), |(done, total), s| {
+        let (d, t) = progress(&s.children);
+        (done + d + s.finished as usize, total + t + 1)
+    })
+}
+
+/// Collects the path of every subtask in display order.
+pub fn flatten(items: &[Subtask], prefix: &[usize], out: &mut Vec<SubtaskPath>) {
+    for (i, s) in items.iter().enumerate() {
+        let mut path = prefix.to_vec();
+        path.push(i);
+        out.push(path.clone());
+        flatten(&s.children, &path, out);
+    }
+}
+
+pub fn reset(items: &mut [Subtask]) {
+    for s in items.iter_mut() {
+        s.finished = false;
+        reset(&mut s.children);
+    }
+}
+
+/// Inserts `sub` after the subtask at `path`, or as the last top level subtask if `path` is empty.
+pub fn insert_after(items: &mut Vec<Subtask>, path: &[usize], sub: Subtask) -> SubtaskPath {
+    let Some((last, _)) = path.split_last() else {
+        items.push(sub);
+        return vec![items.len() - 1];
+    };
+    match siblings_mut(items, path) {
+        Some(siblings) => {
+            siblings.insert(last + 1, sub);
+            let mut new_path = path.to_vec();
+            *new_path.last_mut().unwrap() += 1;
+            new_path
+        }
+        None => {
+            items.push(sub);
+            vec![items.len() - 1]
+        }
+    }
+}
+
+pub fn remove(items: &mut Vec<Subtask>, path: &[usize]) -> Option<Subtask> {
+    let (last, _) = path.split_last()?;
+    let siblings = siblings_mut(items, path)?;
+    if *last < siblings.len() {
+        Some(siblings.remove(*last))
+    } else {
+        None
+    }
+}
+
+/// Makes the subtask at `path` the last child of the sibling above it.
+pub fn indent(items: &mut Vec<Subtask>, path: &[usize]) -> Option<SubtaskPath> {
+    let (last, parent) = path.split_last()?;
+    if *last == 0 {
+        return None;
+    }
+    let siblings = siblings_mut(items, path)?;
+    let sub = siblings.remove(*last);
+    let new_parent = &mut siblings[last - 1];
+    new_parent.children.push(sub);
+
+    let mut new_path = parent.to_vec();
+    new_path.push(last - 1);
+    new_path.push(new_parent.children.len() - 1);
+    Some(new_path)
+}
+
+/// Moves the subtask at `path` out of its parent, placing it right after the parent.
+pub fn outdent(items: &mut Vec<Subtask>, path: &[usize]) -> Option<SubtaskPath> {
+    if path.len() < 2 {
+        return None;
+    }
+    let sub = remove(items, path)?;
+    let parent = &path[..path.len() - 1];
+    Some(insert_after(items, parent, sub))
+}
+
+fn set_all(items: &mut [Subtask], finished: bool) {
+    for s in items.iter_mut() {
+        s.finished = finished;
+        set_all(&mut s.children, finished);
+    }
+}
+
+/// Marks every subtask that has children as finished exactly when all of its children are.
+pub fn sync_parents(items: &mut [Subtask]) {
+    for s in items.iter_mut().filter(|s| !s.children.is_empty()) {
+        sync_parents(&mut s.children);
+        s.finished = s.children.iter().all(|c| c.finished);
+    }
+}
+
+/// Toggles the subtask at `path`. With `complete_parents` its children and parents
+/// follow along, so a parent is finished exactly when all of its children are.
+/// Returns the new state of the toggled subtask.
+pub fn toggle(items: &mut [Subtask], path: &[usize], complete_parents: bool) -> Option<bool> {
+    let sub = get_mut(items, path)?;
+    sub.finished = !sub.finished;
+    let finished = sub.finished;
+
+    if complete_parents {
+        set_all(&mut sub.children, finished);
+        for depth in (1..path.len()).rev() {
+            let parent = get_mut(items, &path[..depth])?;
+            parent.finished = parent.children.iter().all(|c| c.finished);
+        }
+    }
+    Some(finished)
+}
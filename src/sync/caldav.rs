@@ -0,0 +1,568 @@
+use super::{Conflict, Resolution, SyncReport};
+use crate::components::todo_list::{Todo, TodoId, TodoMetadata};
+use crate::formats::ical;
+use crate::store::TodoListSerde;
+use anyhow::{anyhow, bail, Result};
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+static REPORT_BODY: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<c:calendar-query xmlns:d="DAV:" xmlns:c="urn:ietf:params:xml:ns:caldav">
+  <d:prop><d:getetag/></d:prop>
+  <c:filter>
+    <c:comp-filter name="VCALENDAR"><c:comp-filter name="VTODO"/></c:comp-filter>
+  </c:filter>
+</c:calendar-query>"#;
+
+/// The server credentials and the collection each list is synced with, stored in `caldav.ron`.
+#[derive(Serialize, Deserialize, Default)]
+struct CalDavConfig {
+    username: String,
+    password: String,
+    /// List names mapped to collection urls, e.g. `https://dav.example.com/calendars/me/tasks/`.
+    collections: HashMap<String, String>,
+}
+
+/// What each todo looked like on both sides after the last sync, stored in `caldav-state.ron`.
+#[derive(Serialize, Deserialize, Default)]
+struct SyncState {
+    lists: HashMap<String, Vec<Synced>>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct Synced {
+    id: TodoId,
+    href: String,
+    uid: String,
+    etag: String,
+    // the todo as a VTODO, to notice local changes
+    fingerprint: String,
+}
+
+pub fn sync(list: &str, data: &mut TodoListSerde) -> Result<SyncReport> {
+    let client = Client::load(list)?;
+    let mut state: SyncState = confy::load("tood", Some("caldav-state"))?;
+    let entries = state.lists.entry(list.to_string()).or_default();
+    let report = sync_with(&client, entries, data)?;
+    confy::store("tood", Some("caldav-state"), &state)?;
+    Ok(report)
+}
+
+pub fn resolve(
+    list: &str,
+    data: &mut TodoListSerde,
+    conflict: &Conflict,
+    resolution: Resolution,
+) -> Result<()> {
+    let client = Client::load(list)?;
+    let mut state: SyncState = confy::load("tood", Some("caldav-state"))?;
+    let entries = state.lists.entry(list.to_string()).or_default();
+    resolve_with(&client, entries, data, conflict, resolution)?;
+    confy::store("tood", Some("caldav-state"), &state)?;
+    Ok(())
+}
+
+fn sync_with(
+    client: &Client,
+    entries: &mut Vec<Synced>,
+    data: &mut TodoListSerde,
+) -> Result<SyncReport> {
+    let remote = client.etags()?;
+    let mut report = SyncReport::default();
+    let mut kept = Vec::with_capacity(entries.len());
+    let known: HashSet<String> = entries.iter().map(|e| e.href.clone()).collect();
+
+    for mut entry in entries.drain(..) {
+        let local = data.todos.iter().position(|t| t.id == entry.id);
+        let changed = local.map(|i| fingerprint(&data.todos[i], &entry.uid) != entry.fingerprint);
+
+        match (local, remote.get(&entry.href)) {
+            (None, None) => {}
+            // removed here, so remove it there too unless it was changed there
+            (None, Some(etag)) if *etag == entry.etag => {
+                if client.delete(&entry.href, etag)? {
+                    report.deleted += 1;
+                } else {
+                    kept.push(entry);
+                }
+            }
+            (None, Some(_)) => {
+                let (remote, etag) = client.get(&entry.href)?;
+                let id = data.push(remote);
+                entry.id = id;
+                entry.etag = etag;
+                entry.fingerprint = fingerprint(data.todos.last().unwrap(), &entry.uid);
+                kept.push(entry);
+                report.pulled += 1;
+            }
+            // removed there, but changes made here win over that
+            (Some(i), None) if changed == Some(true) => {
+                let etag = client
+                    .put(&entry.href, &data.todos[i], &entry.uid, None)?
+                    .ok_or_else(|| anyhow!("'{}' was created again on the server", entry.href))?;
+                entry.etag = etag;
+                entry.fingerprint = fingerprint(&data.todos[i], &entry.uid);
+                kept.push(entry);
+                report.pushed += 1;
+            }
+            (Some(i), None) => {
+                data.todos.remove(i);
+                report.deleted += 1;
+            }
+            (Some(i), Some(etag)) => match (changed == Some(true), *etag == entry.etag) {
+                (false, true) => kept.push(entry),
+                (true, true) => {
+                    match client.put(&entry.href, &data.todos[i], &entry.uid, Some(etag))? {
+                        Some(etag) => {
+                            entry.etag = etag;
+                            entry.fingerprint = fingerprint(&data.todos[i], &entry.uid);
+                            report.pushed += 1;
+                        }
+                        // changed on the server after it was listed
+                        None => report.conflicts.extend(check_conflict(
+                            client,
+                            &mut entry,
+                            &data.todos[i],
+                        )?),
+                    }
+                    kept.push(entry);
+                }
+                (false, false) => {
+                    let (remote, etag) = client.get(&entry.href)?;
+                    data.todos[i] = merge(&data.todos[i], remote);
+                    entry.etag = etag;
+                    entry.fingerprint = fingerprint(&data.todos[i], &entry.uid);
+                    kept.push(entry);
+                    report.pulled += 1;
+                }
+                (true, false) => {
+                    report
+                        .conflicts
+                        .extend(check_conflict(client, &mut entry, &data.todos[i])?);
+                    kept.push(entry);
+                }
+            },
+        }
+    }
+
+    // created on the server since the last sync
+    for href in remote.keys().filter(|h| !known.contains(*h)) {
+        let (body, etag) = client.get_raw(href)?;
+        let Some((remote, uid)) = ical::import_with_uids(&body)?.into_iter().next() else {
+            continue;
+        };
+        let id = data.push(remote);
+        let uid = uid.unwrap_or_else(|| ical::default_uid(data.todos.last().unwrap()));
+        kept.push(Synced {
+            id,
+            href: href.clone(),
+            fingerprint: fingerprint(data.todos.last().unwrap(), &uid),
+            uid,
+            etag,
+        });
+        report.pulled += 1;
+    }
+
+    // created here since the last sync
+    let synced: HashSet<TodoId> = kept.iter().map(|e| e.id).collect();
+    for t in data.todos.iter().filter(|t| !synced.contains(&t.id)) {
+        let uid = ical::default_uid(t);
+        let href = format!("{}{uid}.ics", client.collection_path());
+        // it already exists if a previous sync failed after uploading it
+        let etag = match client.put(&href, t, &uid, None)? {
+            Some(etag) => etag,
+            None => client.get_raw(&href)?.1,
+        };
+        kept.push(Synced {
+            id: t.id,
+            href,
+            fingerprint: fingerprint(t, &uid),
+            uid,
+            etag,
+        });
+        report.pushed += 1;
+    }
+
+    *entries = kept;
+    Ok(report)
+}
+
+fn resolve_with(
+    client: &Client,
+    entries: &mut [Synced],
+    data: &mut TodoListSerde,
+    conflict: &Conflict,
+    resolution: Resolution,
+) -> Result<()> {
+    let entry = entries
+        .iter_mut()
+        .find(|e| e.id == conflict.id)
+        .ok_or_else(|| anyhow!("'{}' is no longer synced", conflict.local.name))?;
+    let todo = data
+        .get_mut(conflict.id)
+        .ok_or_else(|| anyhow!("'{}' no longer exists", conflict.local.name))?;
+
+    match resolution {
+        Resolution::KeepLocal => {
+            entry.etag = client
+                .put(&entry.href, todo, &entry.uid, Some(&conflict.etag))?
+                .ok_or_else(|| {
+                    anyhow!("'{}' changed on the server again, sync again", todo.name)
+                })?;
+        }
+        Resolution::KeepRemote => {
+            *todo = conflict.remote.clone();
+            entry.etag = conflict.etag.clone();
+        }
+    }
+    entry.fingerprint = fingerprint(todo, &entry.uid);
+    Ok(())
+}
+
+/// Fetches the server's version of a todo changed on both sides. Unless both sides made
+/// the same change, like after a sync that failed halfway, this is a conflict.
+fn check_conflict(client: &Client, entry: &mut Synced, local: &Todo) -> Result<Option<Conflict>> {
+    let (remote, etag) = client.get(&entry.href)?;
+    let remote = merge(local, remote);
+    if fingerprint(&remote, &entry.uid) == fingerprint(local, &entry.uid) {
+        entry.etag = etag;
+        entry.fingerprint = fingerprint(local, &entry.uid);
+        return Ok(None);
+    }
+    Ok(Some(Conflict {
+        id: local.id,
+        local: local.clone(),
+        remote,
+        etag,
+    }))
+}
+
+/// Takes everything a VTODO holds from `remote`, keeping what only tood knows about.
+fn merge(local: &Todo, remote: Todo) -> Todo {
+    Todo {
+        id: local.id,
+        subtasks: local.subtasks.clone(),
+        metadata: TodoMetadata {
+            remind_before: local.metadata.remind_before,
+            last_completed: local.metadata.last_completed,
+            ..remote.metadata
+        },
+        ..remote
+    }
+}
+
+// DTSTAMP is the time the VTODO was written, which changes every time
+fn fingerprint(t: &Todo, uid: &str) -> String {
+    ical::export_one(t, uid)
+        .lines()
+        .filter(|l| !l.starts_with("DTSTAMP:"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+struct Client {
+    collection: String,
+    auth: Option<String>,
+    agent: ureq::Agent,
+}
+
+impl Client {
+    fn new(collection: &str, username: &str, password: &str) -> Self {
+        let mut collection = collection.to_string();
+        if !collection.ends_with('/') {
+            collection.push('/');
+        }
+        let auth = (!username.is_empty()).then(|| {
+            let credentials = format!("{username}:{password}");
+            format!(
+                "Basic {}",
+                base64::engine::general_purpose::STANDARD.encode(credentials)
+            )
+        });
+        Self {
+            collection,
+            auth,
+            agent: ureq::AgentBuilder::new().build(),
+        }
+    }
+
+    fn load(list: &str) -> Result<Self> {
+        let config: CalDavConfig = confy::load("tood", Some("caldav"))?;
+        let Some(collection) = config.collections.get(list) else {
+            let path = confy::get_configuration_file_path("tood", Some("caldav"))?;
+            bail!(
+                "no CalDAV collection set up for list '{list}', add one to {}",
+                path.display()
+            );
+        };
+        Ok(Self::new(collection, &config.username, &config.password))
+    }
+
+    // `scheme://host:port` of the collection
+    fn origin(&self) -> &str {
+        let start = self.collection.find("://").map_or(0, |i| i + 3);
+        match self.collection[start..].find('/') {
+            Some(i) => &self.collection[..start + i],
+            None => &self.collection,
+        }
+    }
+
+    fn collection_path(&self) -> &str {
+        &self.collection[self.origin().len()..]
+    }
+
+    /// Hrefs are kept as absolute paths, the way servers usually report them.
+    fn href_of(&self, href: &str) -> String {
+        match href.strip_prefix(self.origin()) {
+            Some(path) if href.contains("://") => path.to_string(),
+            _ => href.to_string(),
+        }
+    }
+
+    fn request(&self, method: &str, href: &str) -> ureq::Request {
+        let req = self
+            .agent
+            .request(method, &format!("{}{href}", self.origin()));
+        match &self.auth {
+            Some(auth) => req.set("Authorization", auth),
+            None => req,
+        }
+    }
+
+    /// Lists the href and ETag of every VTODO in the collection.
+    fn etags(&self) -> Result<HashMap<String, String>> {
+        let body = self
+            .request("REPORT", self.collection_path())
+            .set("Depth", "1")
+            .set("Content-Type", "application/xml; charset=utf-8")
+            .send_string(REPORT_BODY)?
+            .into_string()?;
+        let doc = roxmltree::Document::parse(&body)?;
+
+        let mut etags = HashMap::new();
+        for response in doc
+            .descendants()
+            .filter(|n| n.tag_name().name() == "response")
+        {
+            let text = |name: &str| {
+                response
+                    .descendants()
+                    .find(|n| n.tag_name().name() == name)
+                    .and_then(|n| n.text())
+                    .map(|t| t.trim().to_string())
+            };
+            if let (Some(href), Some(etag)) = (text("href"), text("getetag")) {
+                etags.insert(self.href_of(&href), etag);
+            }
+        }
+        Ok(etags)
+    }
+
+    fn get_raw(&self, href: &str) -> Result<(String, String)> {
+        let resp = self.request("GET", href).call()?;
+        let etag = resp.header("ETag").unwrap_or_default().to_string();
+        Ok((resp.into_string()?, etag))
+    }
+
+    fn get(&self, href: &str) -> Result<(Todo, String)> {
+        let (body, etag) = self.get_raw(href)?;
+        let todo = ical::import(&body)?
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow!("'{href}' holds no VTODO"))?;
+        Ok((todo, etag))
+    }
+
+    /// Uploads `t`, only replacing the version with `etag`, or only creating it if `etag`
+    /// is `None`. Returns the new ETag, or `None` if the server holds another version.
+    fn put(&self, href: &str, t: &Todo, uid: &str, etag: Option<&str>) -> Result<Option<String>> {
+        let req = self
+            .request("PUT", href)
+            .set("Content-Type", "text/calendar; charset=utf-8");
+        let req = match etag {
+            Some(etag) => req.set("If-Match", etag),
+            None => req.set("If-None-Match", "*"),
+        };
+        match req.send_string(&ical::export_one(t, uid)) {
+            // servers may leave out the ETag, which makes the next sync pull the todo once
+            Ok(resp) => Ok(Some(resp.header("ETag").unwrap_or_default().to_string())),
+            Err(ureq::Error::Status(412, _)) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Deletes the version with `etag`, returning false if the server holds another version.
+    fn delete(&self, href: &str, etag: &str) -> Result<bool> {
+        match self.request("DELETE", href).set("If-Match", etag).call() {
+            Ok(_) | Err(ureq::Error::Status(404, _)) => Ok(true),
+            Err(ureq::Error::Status(412, _)) => Ok(false),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::mock_server::{MockServer, COLLECTION};
+
+    fn setup() -> (MockServer, Client) {
+        let client = Client::new("", "user", "secret");
+        let server = MockServer::start(client.auth.as_deref().unwrap());
+        let client = Client::new(&server.url, "user", "secret");
+        (server, client)
+    }
+
+    fn todo(name: &str) -> Todo {
+        Todo {
+            name: name.into(),
+            ..Default::default()
+        }
+    }
+
+    fn names(data: &TodoListSerde) -> Vec<&str> {
+        data.todos.iter().map(|t| t.name.as_str()).collect()
+    }
+
+    fn vtodo(uid: &str, summary: &str) -> String {
+        format!("BEGIN:VCALENDAR\r\nBEGIN:VTODO\r\nUID:{uid}\r\nSUMMARY:{summary}\r\nEND:VTODO\r\nEND:VCALENDAR\r\n")
+    }
+
+    #[test]
+    fn pushes_new_todos_once() {
+        let (server, client) = setup();
+        let mut data = TodoListSerde::default();
+        data.push(todo("write report"));
+        data.push(todo("buy milk"));
+        let mut entries = Vec::new();
+
+        let report = sync_with(&client, &mut entries, &mut data).unwrap();
+        assert_eq!(report.pushed, 2);
+        assert_eq!(server.items().len(), 2);
+        assert!(server
+            .items()
+            .values()
+            .any(|b| b.contains("SUMMARY:buy milk")));
+
+        let report = sync_with(&client, &mut entries, &mut data).unwrap();
+        assert_eq!((report.pushed, report.pulled, report.deleted), (0, 0, 0));
+    }
+
+    #[test]
+    fn pulls_remote_changes() {
+        let (server, client) = setup();
+        let mut data = TodoListSerde::default();
+        data.push(todo("write report"));
+        let mut entries = Vec::new();
+        sync_with(&client, &mut entries, &mut data).unwrap();
+
+        server.put("other.ics", &vtodo("other", "call mom"));
+        let uid = entries[0].uid.clone();
+        let href = entries[0].href.trim_start_matches(COLLECTION).to_string();
+        server.put(&href, &vtodo(&uid, "write better report"));
+
+        let report = sync_with(&client, &mut entries, &mut data).unwrap();
+        assert_eq!(report.pulled, 2);
+        assert_eq!(names(&data), ["write better report", "call mom"]);
+
+        server.remove(&entries[1].href);
+        let report = sync_with(&client, &mut entries, &mut data).unwrap();
+        assert_eq!(report.deleted, 1);
+        assert_eq!(names(&data), ["write better report"]);
+    }
+
+    #[test]
+    fn pushes_local_changes_and_removals() {
+        let (server, client) = setup();
+        let mut data = TodoListSerde::default();
+        let id = data.push(todo("write report"));
+        data.push(todo("buy milk"));
+        let mut entries = Vec::new();
+        sync_with(&client, &mut entries, &mut data).unwrap();
+
+        data.get_mut(id).unwrap().metadata.finished = true;
+        data.todos.pop();
+        let report = sync_with(&client, &mut entries, &mut data).unwrap();
+        assert_eq!((report.pushed, report.deleted), (1, 1));
+
+        let items = server.items();
+        assert_eq!(items.len(), 1);
+        assert!(items.values().all(|b| b.contains("STATUS:COMPLETED")));
+    }
+
+    #[test]
+    fn reports_and_resolves_conflicts() {
+        let (server, client) = setup();
+        let mut data = TodoListSerde::default();
+        let id = data.push(todo("write report"));
+        let mut entries = Vec::new();
+        sync_with(&client, &mut entries, &mut data).unwrap();
+
+        let href = entries[0].href.trim_start_matches(COLLECTION).to_string();
+        server.put(&href, &vtodo(&entries[0].uid, "remote name"));
+        data.get_mut(id).unwrap().name = "local name".into();
+
+        let report = sync_with(&client, &mut entries, &mut data).unwrap();
+        assert_eq!(report.conflicts.len(), 1);
+        let conflict = &report.conflicts[0];
+        assert_eq!(conflict.local.name, "local name");
+        assert_eq!(conflict.remote.name, "remote name");
+
+        // unresolved conflicts come up again
+        let again = sync_with(&client, &mut entries, &mut data).unwrap();
+        assert_eq!(again.conflicts.len(), 1);
+
+        resolve_with(
+            &client,
+            &mut entries,
+            &mut data,
+            conflict,
+            Resolution::KeepLocal,
+        )
+        .unwrap();
+        assert!(server
+            .items()
+            .values()
+            .all(|b| b.contains("SUMMARY:local name")));
+        let report = sync_with(&client, &mut entries, &mut data).unwrap();
+        assert!(report.conflicts.is_empty());
+        assert_eq!((report.pushed, report.pulled), (0, 0));
+    }
+
+    #[test]
+    fn keeping_the_remote_version_replaces_the_local_one() {
+        let (server, client) = setup();
+        let mut data = TodoListSerde::default();
+        let id = data.push(todo("write report"));
+        let mut entries = Vec::new();
+        sync_with(&client, &mut entries, &mut data).unwrap();
+
+        let href = entries[0].href.trim_start_matches(COLLECTION).to_string();
+        server.put(&href, &vtodo(&entries[0].uid, "remote name"));
+        data.get_mut(id).unwrap().name = "local name".into();
+
+        let report = sync_with(&client, &mut entries, &mut data).unwrap();
+        let conflict = &report.conflicts[0];
+        resolve_with(
+            &client,
+            &mut entries,
+            &mut data,
+            conflict,
+            Resolution::KeepRemote,
+        )
+        .unwrap();
+        assert_eq!(names(&data), ["remote name"]);
+
+        let report = sync_with(&client, &mut entries, &mut data).unwrap();
+        assert_eq!((report.pushed, report.pulled), (0, 0));
+    }
+
+    #[test]
+    fn rejects_wrong_credentials() {
+        let (server, _) = setup();
+        let client = Client::new(&server.url, "user", "wrong");
+        let mut data = TodoListSerde::default();
+        assert!(sync_with(&client, &mut Vec::new(), &mut data).is_err());
+    }
+}
@@ -0,0 +1,168 @@
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+pub static COLLECTION: &str = "/calendars/test/tasks/";
+
+#[derive(Default)]
+struct Store {
+    // href -> (calendar data, etag)
+    items: HashMap<String, (String, String)>,
+    next_etag: u32,
+}
+
+impl Store {
+    fn write(&mut self, href: &str, body: String) -> String {
+        self.next_etag += 1;
+        let etag = format!("\"{}\"", self.next_etag);
+        self.items.insert(href.to_string(), (body, etag.clone()));
+        etag
+    }
+}
+
+/// A CalDAV server holding a single collection in memory, just capable enough to sync with.
+pub struct MockServer {
+    pub url: String,
+    store: Arc<Mutex<Store>>,
+}
+
+impl MockServer {
+    /// Starts serving on a free port, accepting only the given basic auth header.
+    pub fn start(auth: &str) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}{COLLECTION}", listener.local_addr().unwrap());
+        let store = Arc::new(Mutex::new(Store::default()));
+
+        let (auth, server_store) = (auth.to_string(), store.clone());
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                handle(stream, &auth, &server_store);
+            }
+        });
+        Self { url, store }
+    }
+
+    /// Changes or creates an item the way another client would.
+    pub fn put(&self, name: &str, body: &str) {
+        let href = format!("{COLLECTION}{name}");
+        self.store.lock().unwrap().write(&href, body.to_string());
+    }
+
+    pub fn remove(&self, href: &str) {
+        self.store.lock().unwrap().items.remove(href);
+    }
+
+    pub fn items(&self) -> HashMap<String, String> {
+        let store = self.store.lock().unwrap();
+        store
+            .items
+            .iter()
+            .map(|(href, (body, _))| (href.clone(), body.clone()))
+            .collect()
+    }
+}
+
+struct Request {
+    method: String,
+    path: String,
+    headers: HashMap<String, String>,
+    body: String,
+}
+
+fn read_request(stream: &TcpStream) -> Option<Request> {
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    reader.read_line(&mut line).ok()?;
+    let mut parts = line.split_whitespace();
+    let (method, path) = (parts.next()?.to_string(), parts.next()?.to_string());
+
+    let mut headers = HashMap::new();
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line).ok()?;
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        let (name, value) = line.split_once(':')?;
+        headers.insert(name.to_lowercase(), value.trim().to_string());
+    }
+
+    let len = headers
+        .get("content-length")
+        .and_then(|l| l.parse().ok())
+        .unwrap_or(0);
+    let mut body = vec![0; len];
+    reader.read_exact(&mut body).ok()?;
+    Some(Request {
+        method,
+        path,
+        headers,
+        body: String::from_utf8(body).ok()?,
+    })
+}
+
+fn respond(mut stream: TcpStream, status: &str, etag: Option<&str>, body: &str) {
+    let etag = etag.map(|e| format!("ETag: {e}\r\n")).unwrap_or_default();
+    let _ = write!(
+        stream,
+        "HTTP/1.1 {status}\r\n{etag}Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+}
+
+fn handle(stream: TcpStream, auth: &str, store: &Mutex<Store>) {
+    let Some(req) = read_request(&stream) else {
+        return;
+    };
+    if req.headers.get("authorization").map(String::as_str) != Some(auth) {
+        return respond(stream, "401 Unauthorized", None, "");
+    }
+
+    let mut store = store.lock().unwrap();
+    let current = store.items.get(&req.path).map(|(_, etag)| etag.clone());
+    let if_match = req.headers.get("if-match");
+    let precondition_failed = match (if_match, req.headers.get("if-none-match")) {
+        (Some(expected), _) => current.as_ref() != Some(expected),
+        (None, Some(_)) => current.is_some(),
+        (None, None) => false,
+    };
+
+    match req.method.as_str() {
+        "REPORT" if req.path == COLLECTION => {
+            let mut xml = String::from(r#"<?xml version="1.0"?><d:multistatus xmlns:d="DAV:">"#);
+            for (href, (_, etag)) in store.items.iter() {
+                xml.push_str(&format!(
+                    "<d:response><d:href>{href}</d:href><d:propstat><d:prop>\
+                     <d:getetag>{etag}</d:getetag></d:prop>\
+                     <d:status>HTTP/1.1 200 OK</d:status></d:propstat></d:response>"
+                ));
+            }
+            xml.push_str("</d:multistatus>");
+            respond(stream, "207 Multi-Status", None, &xml);
+        }
+        "GET" => match store.items.get(&req.path) {
+            Some((body, etag)) => respond(stream, "200 OK", Some(etag), body),
+            None => respond(stream, "404 Not Found", None, ""),
+        },
+        "PUT" | "DELETE" if precondition_failed => {
+            respond(stream, "412 Precondition Failed", None, "")
+        }
+        "PUT" => {
+            let status = if current.is_some() {
+                "204 No Content"
+            } else {
+                "201 Created"
+            };
+            let etag = store.write(&req.path, req.body);
+            respond(stream, status, Some(&etag), "");
+        }
+        "DELETE" => match store.items.remove(&req.path) {
+            Some(_) => respond(stream, "204 No Content", None, ""),
+            None => respond(stream, "404 Not Found", None, ""),
+        },
+        _ => respond(stream, "405 Method Not Allowed", None, ""),
+    }
+}
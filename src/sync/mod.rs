@@ -0,0 +1,78 @@
+#[cfg(feature = "caldav")]
+mod caldav;
+#[cfg(all(test, feature = "caldav"))]
+mod mock_server;
+
+use crate::components::todo_list::{Todo, TodoId};
+use crate::store::TodoListSerde;
+use anyhow::Result;
+
+/// A todo that was changed both locally and on the server since the last sync.
+#[derive(Clone, Debug)]
+#[cfg_attr(not(feature = "caldav"), allow(dead_code))]
+pub struct Conflict {
+    pub id: TodoId,
+    pub local: Todo,
+    pub remote: Todo,
+    // the version of `remote` on the server
+    etag: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Resolution {
+    KeepLocal,
+    KeepRemote,
+}
+
+#[derive(Debug, Default)]
+pub struct SyncReport {
+    pub pushed: usize,
+    pub pulled: usize,
+    pub deleted: usize,
+    pub conflicts: Vec<Conflict>,
+}
+
+impl SyncReport {
+    pub fn describe(&self) -> String {
+        let mut msg = format!(
+            "Synced: {} pushed, {} pulled, {} deleted",
+            self.pushed, self.pulled, self.deleted
+        );
+        if !self.conflicts.is_empty() {
+            msg.push_str(&format!(", {} conflicts", self.conflicts.len()));
+        }
+        msg
+    }
+}
+
+/// Pushes local changes of `list` to its CalDAV collection and pulls remote ones into `data`.
+/// Todos changed on both sides are left alone and returned as conflicts.
+#[cfg(feature = "caldav")]
+pub fn sync(list: &str, data: &mut TodoListSerde) -> Result<SyncReport> {
+    caldav::sync(list, data)
+}
+
+#[cfg(feature = "caldav")]
+pub fn resolve(
+    list: &str,
+    data: &mut TodoListSerde,
+    conflict: &Conflict,
+    resolution: Resolution,
+) -> Result<()> {
+    caldav::resolve(list, data, conflict, resolution)
+}
+
+#[cfg(not(feature = "caldav"))]
+pub fn sync(_list: &str, _data: &mut TodoListSerde) -> Result<SyncReport> {
+    anyhow::bail!("tood was built without CalDAV support, rebuild it with `--features caldav`")
+}
+
+#[cfg(not(feature = "caldav"))]
+pub fn resolve(
+    _list: &str,
+    _data: &mut TodoListSerde,
+    _conflict: &Conflict,
+    _resolution: Resolution,
+) -> Result<()> {
+    anyhow::bail!("tood was built without CalDAV support, rebuild it with `--features caldav`")
+}
@@ -0,0 +1,27 @@
+/// Splits user input like `#work, home` into tags, dropping the `#` and duplicates.
+pub fn parse(s: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for tag in s
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .map(|t| t.trim_start_matches('#').to_lowercase())
+        .filter(|t| !t.is_empty())
+    {
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    tags
+}
+
+/// Formats tags the way they are typed, e.g. `#work #home`.
+pub fn format(tags: &[String]) -> String {
+    tags.iter()
+        .map(|t| format!("#{t}"))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Whether `tags` contains every tag of `filter`.
+pub fn matches(tags: &[String], filter: &[String]) -> bool {
+    filter.iter().all(|f| tags.contains(f))
+}
@@ -1,4 +1,5 @@
 use super::theme_config::ThemeConfig;
+use crate::priority::Priority;
 use crate::{components::notification::FlashMsg, config::Config};
 use kanal::Sender;
 use ratatui::style::Color;
@@ -8,6 +9,22 @@ use tui_utils::shared::Shared;
 
 pub type SharedTheme = Rc<ToodTheme>;
 
+/// How todos of a priority level are marked in the list.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PriorityStyle {
+    pub color: Color,
+    pub marker: String,
+}
+
+impl PriorityStyle {
+    fn new(color: Color, marker: &str) -> Self {
+        Self {
+            color,
+            marker: marker.into(),
+        }
+    }
+}
+
 #[derive(Debug, Shared, Serialize, Deserialize)]
 pub struct ToodTheme {
     pub border: Color,
@@ -18,6 +35,16 @@ pub struct ToodTheme {
     pub todo_title: Color,
     pub recurring_todo_title: Color,
     pub completed_todo_title: Color,
+    pub due_overdue: Color,
+    pub due_today: Color,
+    pub due_soon: Color,
+    pub tag_fg: Color,
+    /// Tags get their background from this palette, picked by the tag's name.
+    pub tag_colors: Vec<Color>,
+    pub priority_low: PriorityStyle,
+    pub priority_medium: PriorityStyle,
+    pub priority_high: PriorityStyle,
+    pub priority_urgent: PriorityStyle,
     pub selected_fg: Color,
     pub selected_bg: Color,
     pub scrollbar: Color,
@@ -43,6 +70,22 @@ impl Default for ToodTheme {
             todo_title: Color::Reset,
             recurring_todo_title: Color::Blue,
             completed_todo_title: Color::Green,
+            due_overdue: Color::Red,
+            due_today: Color::Yellow,
+            due_soon: Color::Cyan,
+            tag_fg: Color::Black,
+            tag_colors: vec![
+                Color::Cyan,
+                Color::Magenta,
+                Color::Yellow,
+                Color::LightBlue,
+                Color::LightGreen,
+                Color::LightRed,
+            ],
+            priority_low: PriorityStyle::new(Color::Cyan, "·"),
+            priority_medium: PriorityStyle::new(Color::Yellow, "!"),
+            priority_high: PriorityStyle::new(Color::LightRed, "!!"),
+            priority_urgent: PriorityStyle::new(Color::Red, "!!!"),
             selected_fg: Color::Blue,
             selected_bg: Color::Indexed(8),
             scrollbar: Color::Blue,
@@ -59,6 +102,27 @@ impl Default for ToodTheme {
 }
 
 impl ToodTheme {
+    pub fn priority_style(&self, priority: Priority) -> Option<&PriorityStyle> {
+        match priority {
+            Priority::None => None,
+            Priority::Low => Some(&self.priority_low),
+            Priority::Medium => Some(&self.priority_medium),
+            Priority::High => Some(&self.priority_high),
+            Priority::Urgent => Some(&self.priority_urgent),
+        }
+    }
+
+    pub fn tag_color(&self, tag: &str) -> Color {
+        if self.tag_colors.is_empty() {
+            return self.todo_title;
+        }
+        // a stable pick so a tag keeps its color between runs
+        let hash = tag
+            .bytes()
+            .fold(0usize, |h, b| h.wrapping_mul(31).wrapping_add(b as usize));
+        self.tag_colors[hash % self.tag_colors.len()]
+    }
+
     pub fn init(tx: Sender<FlashMsg>) -> SharedTheme {
         match ThemeConfig::read_from_file("theme") {
             Ok(Some(theme)) => theme.to_shared(),
@@ -1,4 +1,4 @@
-use super::theme::ToodTheme;
+use super::theme::{PriorityStyle, ToodTheme};
 use crate::config::Config;
 use ratatui::style::Color;
 use serde::{Deserialize, Serialize};
@@ -14,6 +14,15 @@ pub struct ThemeConfig {
     pub todo_title: Option<Color>,
     pub recurring_todo_title: Option<Color>,
     pub completed_todo_title: Option<Color>,
+    pub due_overdue: Option<Color>,
+    pub due_today: Option<Color>,
+    pub due_soon: Option<Color>,
+    pub tag_fg: Option<Color>,
+    pub tag_colors: Option<Vec<Color>>,
+    pub priority_low: Option<PriorityStyle>,
+    pub priority_medium: Option<PriorityStyle>,
+    pub priority_high: Option<PriorityStyle>,
+    pub priority_urgent: Option<PriorityStyle>,
     pub selected_fg: Option<Color>,
     pub selected_bg: Option<Color>,
     pub scrollbar: Option<Color>,
@@ -43,6 +52,15 @@ impl Config for ThemeConfig {
             todo_title:           self.todo_title.unwrap_or(dt.todo_title),
             recurring_todo_title: self.recurring_todo_title.unwrap_or(dt.recurring_todo_title),
             completed_todo_title: self.completed_todo_title.unwrap_or(dt.completed_todo_title),
+            due_overdue:          self.due_overdue.unwrap_or(dt.due_overdue),
+            due_today:            self.due_today.unwrap_or(dt.due_today),
+            due_soon:             self.due_soon.unwrap_or(dt.due_soon),
+            tag_fg:               self.tag_fg.unwrap_or(dt.tag_fg),
+            tag_colors:           self.tag_colors.unwrap_or(dt.tag_colors),
+            priority_low:         self.priority_low.unwrap_or(dt.priority_low),
+            priority_medium:      self.priority_medium.unwrap_or(dt.priority_medium),
+            priority_high:        self.priority_high.unwrap_or(dt.priority_high),
+            priority_urgent:      self.priority_urgent.unwrap_or(dt.priority_urgent),
             selected_fg:          self.selected_fg.unwrap_or(dt.selected_fg),
             selected_bg:          self.selected_bg.unwrap_or(dt.selected_bg),
             scrollbar:            self.scrollbar.unwrap_or(dt.scrollbar),
@@ -7,6 +7,8 @@ use tui_utils::{component::Component, term};
 pub fn run(mut app: App) -> TerminalResult<()> {
     let mut terminal = term::init().unwrap();
     loop {
+        // let time dependent state such as due labels catch up
+        app.tick()?;
         terminal.draw(|f| ui(f, &mut app))?;
 
         // then handle input events
@@ -17,6 +19,13 @@ pub fn run(mut app: App) -> TerminalResult<()> {
                 AppState::EditTodo => app.todo_input.handle_input(ev),
                 AppState::Find => app.skimmer.handle_input(ev),
                 AppState::DueDate => app.due_date.handle_input(ev),
+                AppState::Lists | AppState::MoveToList => app.list_switcher.handle_input(ev),
+                AppState::AddSubtask => app.subtask_input.handle_input(ev),
+                AppState::TagFilter => app.tag_filter.handle_input(ev),
+                AppState::SyncConflict => app.sync_conflict.handle_input(ev),
+                AppState::DiskChanged => app.disk_changed.handle_input(ev),
+                AppState::Calendar => app.calendar_view.handle_input(ev),
+                AppState::Agenda => app.agenda.handle_input(ev),
             },
             // other term events, we dont handle them in this example
             Ok(Some(_)) => Ok(AppMessage::NoAction),
@@ -34,7 +43,14 @@ pub fn run(mut app: App) -> TerminalResult<()> {
             Ok(AppMessage::InputState(state)) => app.update_state(state)?,
             Ok(AppMessage::Skimmer(skim_action)) => app.perform_skimmer_action(skim_action),
             Ok(AppMessage::UpdateList(list_action)) => app.todo_list_action(list_action)?,
+            Ok(AppMessage::Lists(lists_action)) => app.lists_action(lists_action)?,
+            Ok(AppMessage::SetTagFilter(filter)) => app.set_tag_filter(filter),
             Ok(AppMessage::SetDueDate(d)) => app.set_due_date(d),
+            Ok(AppMessage::JumpToTodo(id)) => app.jump_to_todo(id)?,
+            Ok(AppMessage::Reschedule(id, date)) => app.reschedule(id, date)?,
+            Ok(AppMessage::SyncConflicts(c)) => app.show_conflicts(c)?,
+            Ok(AppMessage::ResolveConflict(r)) => app.resolve_conflict(r)?,
+            Ok(AppMessage::ResolveDiskConflict(c)) => app.resolve_disk_conflict(c)?,
             Ok(AppMessage::ReInitTerminal) => terminal = term::init().unwrap(),
             Ok(AppMessage::Quit) => {
                 term::restore().unwrap();
@@ -66,6 +82,37 @@ fn ui<B: Backend>(f: &mut Frame<B>, app: &mut App) {
             app.todo_list.draw(f, true);
             app.due_date.draw(f, false);
         }
+        AppState::Lists | AppState::MoveToList => {
+            app.todo_list.draw(f, true);
+            app.list_switcher.draw(f, false);
+        }
+        AppState::TagFilter => {
+            app.todo_list.draw(f, true);
+            app.tag_filter.draw(f, false);
+        }
+        AppState::AddSubtask => {
+            app.todo_list.draw(f, true);
+            app.subtask_input.draw(f, false);
+        }
+        AppState::SyncConflict => {
+            app.todo_list.draw(f, true);
+            app.sync_conflict.draw(f, false);
+        }
+        AppState::DiskChanged => {
+            app.todo_list.draw(f, true);
+            app.disk_changed.draw(f, false);
+        }
+        AppState::Calendar => {
+            app.todo_list.draw(f, true);
+            // keep the hint bar below visible
+            app.calendar_view.hints_height = app.todo_list.hintbar_height(f.size());
+            app.calendar_view.draw(f, false);
+        }
+        AppState::Agenda => {
+            app.todo_list.draw(f, true);
+            app.agenda.hints_height = app.todo_list.hintbar_height(f.size());
+            app.agenda.draw(f, false);
+        }
     }
     // draws notification if it exists
     app.notification.draw(f, false);
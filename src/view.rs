@@ -0,0 +1,239 @@
+use crate::components::todo_list::Todo;
+use anyhow::{bail, Error, Result};
+use chrono::{Datelike, Duration, Local, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+/// The order todos are shown in. The stored order is always the manual one.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SortMode {
+    #[default]
+    Manual,
+    Priority,
+    DueDate,
+    Added,
+    Edited,
+    Alphabetical,
+    UnfinishedFirst,
+}
+
+impl SortMode {
+    pub fn next(self) -> Self {
+        match self {
+            Self::Manual => Self::Priority,
+            Self::Priority => Self::DueDate,
+            Self::DueDate => Self::Added,
+            Self::Added => Self::Edited,
+            Self::Edited => Self::Alphabetical,
+            Self::Alphabetical => Self::UnfinishedFirst,
+            Self::UnfinishedFirst => Self::Manual,
+        }
+    }
+
+    pub fn compare(&self, a: &Todo, b: &Todo) -> Ordering {
+        match self {
+            Self::Manual => Ordering::Equal,
+            Self::Priority => b
+                .metadata
+                .priority
+                .cmp(&a.metadata.priority)
+                .then_with(|| cmp_due_date(a, b)),
+            Self::DueDate => cmp_due_date(a, b),
+            // newest first
+            Self::Added => b.metadata.added_at.cmp(&a.metadata.added_at),
+            Self::Edited => {
+                let edited = |t: &Todo| t.metadata.edited_at.unwrap_or(t.metadata.added_at);
+                edited(b).cmp(&edited(a))
+            }
+            Self::Alphabetical => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+            Self::UnfinishedFirst => is_done(a).cmp(&is_done(b)),
+        }
+    }
+}
+
+/// Earlier due dates first, todos without one last.
+fn cmp_due_date(a: &Todo, b: &Todo) -> Ordering {
+    match (a.metadata.due_date, b.metadata.due_date) {
+        (Some(a), Some(b)) => a.cmp(&b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+// recurring todos are never done for good
+fn is_done(t: &Todo) -> bool {
+    t.metadata.finished && !t.metadata.is_recurring()
+}
+
+impl fmt::Display for SortMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Manual => "manual",
+            Self::Priority => "priority",
+            Self::DueDate => "due date",
+            Self::Added => "added",
+            Self::Edited => "edited",
+            Self::Alphabetical => "name",
+            Self::UnfinishedFirst => "unfinished first",
+        };
+        write!(f, "{name}")
+    }
+}
+
+impl FromStr for SortMode {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(match s.trim().to_lowercase().as_str() {
+            "manual" => Self::Manual,
+            "priority" => Self::Priority,
+            "due" | "due-date" => Self::DueDate,
+            "added" => Self::Added,
+            "edited" => Self::Edited,
+            "name" | "alphabetical" => Self::Alphabetical,
+            "unfinished" => Self::UnfinishedFirst,
+            _ => bail!(
+                "unknown sort order '{s}', expected manual, priority, due, added, edited, name or unfinished"
+            ),
+        })
+    }
+}
+
+/// Splits the list into sections with a header each.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum GroupMode {
+    #[default]
+    None,
+    DueDate,
+    Status,
+}
+
+static DUE_DATE_GROUPS: [&str; 6] = [
+    "Overdue",
+    "Today",
+    "This week",
+    "Later",
+    "No date",
+    "Finished",
+];
+static STATUS_GROUPS: [&str; 2] = ["Unfinished", "Finished"];
+
+impl GroupMode {
+    pub fn next(self) -> Self {
+        match self {
+            Self::None => Self::DueDate,
+            Self::DueDate => Self::Status,
+            Self::Status => Self::None,
+        }
+    }
+
+    /// The section a todo belongs to, as an index into the section titles.
+    /// Sections are shown in the order of their index.
+    pub fn group_of(&self, t: &Todo, now: NaiveDateTime) -> usize {
+        match self {
+            Self::None => 0,
+            Self::Status => is_done(t) as usize,
+            Self::DueDate => {
+                if is_done(t) {
+                    return 5;
+                }
+                let Some(due) = t.metadata.due_date else {
+                    return 4;
+                };
+                let today = now.date();
+                let end_of_week =
+                    today + Duration::days(6 - i64::from(today.weekday().num_days_from_monday()));
+                if due < now {
+                    0
+                } else if due.date() == today {
+                    1
+                } else if due.date() <= end_of_week {
+                    2
+                } else {
+                    3
+                }
+            }
+        }
+    }
+
+    pub fn title(&self, group: usize) -> &'static str {
+        match self {
+            Self::None => "",
+            Self::DueDate => DUE_DATE_GROUPS[group],
+            Self::Status => STATUS_GROUPS[group],
+        }
+    }
+}
+
+impl fmt::Display for GroupMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::None => "none",
+            Self::DueDate => "due date",
+            Self::Status => "status",
+        };
+        write!(f, "{name}")
+    }
+}
+
+impl FromStr for GroupMode {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(match s.trim().to_lowercase().as_str() {
+            "none" => Self::None,
+            "due" | "due-date" => Self::DueDate,
+            "status" => Self::Status,
+            _ => bail!("unknown grouping '{s}', expected none, due or status"),
+        })
+    }
+}
+
+/// How the list is presented, remembered between sessions.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ListView {
+    #[serde(default)]
+    pub sort: SortMode,
+    #[serde(default)]
+    pub group: GroupMode,
+}
+
+impl ListView {
+    pub fn load() -> Result<Self> {
+        Ok(confy::load("tood", Some("view"))?)
+    }
+
+    pub fn store(&self) -> Result<()> {
+        confy::store("tood", Some("view"), self)?;
+        Ok(())
+    }
+
+    /// Whether todos are shown in the order they are stored, which move mode relies on.
+    pub fn is_manual(&self) -> bool {
+        self.sort == SortMode::Manual && self.group == GroupMode::None
+    }
+
+    /// Orders todo indices by section and then by the sort mode.
+    pub fn arrange(&self, todos: &[Todo], order: &mut [usize]) {
+        let now = Local::now().naive_local();
+        order.sort_by(|a, b| {
+            let (a, b) = (&todos[*a], &todos[*b]);
+            self.group
+                .group_of(a, now)
+                .cmp(&self.group.group_of(b, now))
+                .then_with(|| self.sort.compare(a, b))
+        });
+    }
+
+    pub fn describe(&self) -> Option<String> {
+        match (self.sort, self.group) {
+            (SortMode::Manual, GroupMode::None) => None,
+            (sort, GroupMode::None) => Some(format!("by {sort}")),
+            (SortMode::Manual, group) => Some(format!("grouped by {group}")),
+            (sort, group) => Some(format!("by {sort}, grouped by {group}")),
+        }
+    }
+}
@@ -4,4 +4,4 @@ pub mod widget;
 
 pub use month::Month;
 pub use state::CalendarState;
-pub use widget::Calendar;
+pub use widget::{Calendar, DayMark};
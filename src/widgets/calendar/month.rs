@@ -1,4 +1,4 @@
-use chrono::{Datelike, Local, NaiveDate, Weekday};
+use chrono::{Datelike, Local, Months, NaiveDate, Weekday};
 
 pub static NAMES: &[&str] = &[
     "January",
@@ -15,8 +15,6 @@ pub static NAMES: &[&str] = &[
     "December",
 ];
 
-pub static MONTH_COUNT: usize = 6;
-
 #[derive(Debug, Clone, Copy)]
 pub struct Month {
     pub name: &'static str,
@@ -27,20 +25,23 @@ pub struct Month {
 }
 
 impl Month {
-    pub fn new(y: i32, m: u32, days: u8) -> Self {
-        // FIXME: handle OOB failure
-        let d = NaiveDate::from_ymd_opt(y, m, 1).unwrap();
-
-        let first_monday = d.week(Weekday::Mon).first_day();
-        let days_since_monday = d.signed_duration_since(first_monday).num_days();
+    /// The month `date` falls in.
+    pub fn containing(date: NaiveDate) -> Self {
+        let first = date.with_day(1).unwrap();
+        let days = first
+            .checked_add_months(Months::new(1))
+            .map(|next| next.signed_duration_since(first).num_days())
+            // the last representable month
+            .unwrap_or(31);
 
-        let name = NAMES[m as usize - 1];
+        let first_monday = first.week(Weekday::Mon).first_day();
+        let days_since_monday = first.signed_duration_since(first_monday).num_days();
 
         Self {
-            name,
-            num: m,
-            year: d.year(),
-            days,
+            name: NAMES[first.month0() as usize],
+            num: first.month(),
+            year: first.year(),
+            days: days as u8,
             padding: days_since_monday as u8,
         }
     }
@@ -49,14 +50,10 @@ impl Month {
         (self.year, self.num)
     }
 
-    pub fn default_day(&self) -> usize {
+    /// Today's day of the month if this is the current month.
+    pub fn today(&self) -> Option<u32> {
         let now = Local::now();
-        // give back current date if the selected
-        // month is the current active month of the year
-        if self.num == now.month() {
-            return now.day() as usize;
-        }
-        0
+        (self.ym() == (now.year(), now.month())).then(|| now.day())
     }
 
     pub fn num_days(&self) -> usize {
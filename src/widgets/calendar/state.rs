@@ -1,89 +1,98 @@
-use super::month::MONTH_COUNT;
+use super::Month;
+use chrono::{Duration, Local, Months, NaiveDate};
 
+/// The selected day, the calendar shows the month it falls in.
 pub struct CalendarState {
-    pub selected_month: usize,
-    pub num_months: usize,
-    pub selected_day: usize,
-    pub num_days: usize,
+    selected: NaiveDate,
 }
 
 impl CalendarState {
-    pub fn new(day: usize, num_days: usize) -> Self {
-        Self {
-            selected_month: 0,
-            num_months: MONTH_COUNT,
-            selected_day: day,
-            num_days,
-        }
-    }
-
-    pub fn with_date(month: usize, day: usize, num_days: usize) -> Result<Self, &'static str> {
-        if month > MONTH_COUNT || day > num_days {
-            return Err("Invalid date");
-        }
-        Ok(Self {
-            selected_month: month,
-            num_months: MONTH_COUNT,
-            selected_day: day,
-            num_days,
-        })
+    pub fn new(selected: NaiveDate) -> Self {
+        Self { selected }
     }
 
-    pub fn set_date(&mut self, day: usize) -> Result<(), &'static str> {
-        if day > self.num_days {
-            return Err("Failed to set date: out of bounds");
-        }
-        self.selected_day = day;
-        Ok(())
+    pub fn today() -> Self {
+        Self::new(Local::now().date_naive())
     }
 
     #[inline(always)]
-    pub fn selected_day(&self) -> u32 {
-        self.selected_day as u32
+    pub fn selected(&self) -> NaiveDate {
+        self.selected
     }
 
     #[inline(always)]
-    pub fn set_num_days(&mut self, nd: usize) {
-        self.selected_day = self.selected_day.min(nd);
-        self.num_days = nd;
+    pub fn select(&mut self, date: NaiveDate) {
+        self.selected = date;
     }
 
-    #[inline(always)]
-    pub fn selected_month(&self) -> usize {
-        self.selected_month
+    pub fn month(&self) -> Month {
+        Month::containing(self.selected)
     }
 
     // ========== Controls ===========
+    // moving past the first or last representable date keeps the selection
 
+    /// Keeps the day of the month, clamped to the length of the new month.
     #[inline(always)]
     pub fn next_month(&mut self) {
-        self.selected_month = (self.num_months - 1).min(self.selected_month + 1);
+        self.shift_months(1);
     }
 
     #[inline(always)]
     pub fn prev_month(&mut self) {
-        self.selected_month = self.selected_month.saturating_sub(1);
+        self.shift_months(-1);
+    }
+
+    #[inline(always)]
+    pub fn next_year(&mut self) {
+        self.shift_months(12);
+    }
+
+    #[inline(always)]
+    pub fn prev_year(&mut self) {
+        self.shift_months(-12);
+    }
+
+    #[inline(always)]
+    pub fn go_to_today(&mut self) {
+        *self = Self::today();
     }
 
     #[inline(always)]
     pub fn right(&mut self) {
-        if self.selected_day < self.num_days {
-            self.selected_day += 1;
-        }
+        self.shift_days(1);
     }
 
     #[inline(always)]
     pub fn left(&mut self) {
-        self.selected_day = self.selected_day.saturating_sub(1);
+        self.shift_days(-1);
     }
 
     #[inline(always)]
     pub fn down(&mut self) {
-        self.selected_day = self.num_days.min(self.selected_day + 7);
+        self.shift_days(7);
     }
 
     #[inline(always)]
     pub fn up(&mut self) {
-        self.selected_day = 1.max(self.selected_day.saturating_sub(7));
+        self.shift_days(-7);
+    }
+
+    fn shift_days(&mut self, days: i64) {
+        if let Some(date) = self.selected.checked_add_signed(Duration::days(days)) {
+            self.selected = date;
+        }
+    }
+
+    fn shift_months(&mut self, months: i32) {
+        let shifted = if months < 0 {
+            self.selected
+                .checked_sub_months(Months::new(months.unsigned_abs()))
+        } else {
+            self.selected.checked_add_months(Months::new(months as u32))
+        };
+        if let Some(date) = shifted {
+            self.selected = date;
+        }
     }
 }
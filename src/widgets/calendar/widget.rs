@@ -1,50 +1,36 @@
-use super::{month::MONTH_COUNT, CalendarState, Month};
+use super::CalendarState;
 use crate::components::utils;
-use chrono::{Datelike, Local, NaiveDate};
+use chrono::Datelike;
 use ratatui::{
     buffer::Buffer as TUIBuffer,
     layout::Rect,
     style::{Color, Modifier, Style},
     widgets::{Block, StatefulWidget, Widget},
 };
+use std::collections::HashMap;
 use tui_utils::style::highlight_style;
 
+/// A short label shown below a day, such as the number of todos due.
+#[derive(Debug, Clone)]
+pub struct DayMark {
+    pub label: String,
+    pub color: Color,
+}
+
 #[derive(Debug, Clone)]
 pub struct Calendar {
-    months: Vec<Month>,
     block: Block<'static>,
     style: Style,
+    // keyed by the day of the shown month
+    marks: HashMap<u32, DayMark>,
 }
 
 impl Default for Calendar {
     fn default() -> Self {
-        let mut months = Vec::with_capacity(MONTH_COUNT);
-        let now = Local::now().date_naive();
-        // FIXME: handle OOB unwrap
-        let mut current_month = NaiveDate::from_ymd_opt(now.year(), now.month(), 1).unwrap();
-
-        for _ in 0..MONTH_COUNT {
-            let year = current_month.year();
-            let month = current_month.month();
-            let (next_month_year, next_month) = if month == 12 {
-                (year + 1, 1)
-            } else {
-                (year, month + 1)
-            };
-            // FIXME: handle OOB unwrap
-            let month_duration = NaiveDate::from_ymd_opt(next_month_year, next_month, 1)
-                .unwrap()
-                .signed_duration_since(current_month);
-
-            months.push(Month::new(year, month, month_duration.num_days() as u8));
-
-            current_month += month_duration;
-        }
-
         Self {
-            months,
             block: utils::default_block("Calendar"),
             style: Style::default(),
+            marks: HashMap::new(),
         }
     }
 }
@@ -54,37 +40,22 @@ impl Calendar {
         self.block = block;
     }
 
-    pub fn get_month_and_index_by_num(&self, month_num: usize) -> Option<(usize, &Month)> {
-        self.months
-            .iter()
-            .enumerate()
-            .find(|m| m.1.num == month_num as u32)
-    }
-
-    pub fn get_month_by_index(&self, i: usize) -> Option<&Month> {
-        if i > self.months.len() {
-            return None;
-        }
-        Some(&self.months[i])
-    }
-
-    pub fn current_month(&self) -> Option<&Month> {
-        if self.months.is_empty() {
-            return None;
-        }
-        Some(&self.months[0])
+    pub fn marks(&mut self, marks: HashMap<u32, DayMark>) {
+        self.marks = marks;
     }
 }
 
 impl StatefulWidget for Calendar {
     type State = CalendarState;
     fn render(self, area: Rect, buf: &mut TUIBuffer, state: &mut Self::State) {
-        let month_i = state.selected_month;
-
-        let (header, padding, num_days) = {
-            let m = self.get_month_by_index(month_i).unwrap();
-            (m.formatted_month_year(), m.padding, m.num_days())
-        };
+        let month = state.month();
+        let (header, padding, num_days) = (
+            month.formatted_month_year(),
+            month.padding,
+            month.num_days(),
+        );
+        let selected_day = state.selected().day() as usize;
+        let today = month.today().map(|d| d as usize);
 
         buf.set_style(area, self.style);
         // get the inner area
@@ -148,8 +119,10 @@ impl StatefulWidget for Calendar {
                 height: cell_height,
             };
 
-            let cell_style = if d == state.selected_day {
+            let cell_style = if d == selected_day {
                 highlight_style()
+            } else if Some(d) == today {
+                Style::default().add_modifier(Modifier::UNDERLINED)
             } else {
                 Style::default()
             };
@@ -159,6 +132,20 @@ impl StatefulWidget for Calendar {
             // render the date number in the center of the current cell
             buf.set_string(cell_area.x + cell_mid, cell_area.y, cell_text, cell_style);
 
+            // and the mark on the free line below it
+            let mark_y = cell_area.y + 1;
+            if let Some(mark) = self.marks.get(&(d as u32)) {
+                if mark_y < calendar_area.y + calendar_area.height {
+                    buf.set_stringn(
+                        cell_area.x + cell_mid,
+                        mark_y,
+                        &mark.label,
+                        cell_width.saturating_sub(cell_mid) as usize,
+                        Style::default().fg(mark.color),
+                    );
+                }
+            }
+
             // check if we are treading boundaries
             if offset_x + cell_width >= calendar_area.x + calendar_area.width {
                 offset_y += cell_area.height;
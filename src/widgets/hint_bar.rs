@@ -13,6 +13,13 @@ pub enum BarType {
     Move,
     Find,
     DueDate,
+    Lists,
+    Subtask,
+    Filter,
+    Conflict,
+    DiskChanged,
+    Calendar,
+    Agenda,
 }
 
 pub struct HintBar {
@@ -52,10 +59,58 @@ impl HintBar {
                 name: "Add",
                 bind: keys.add_todo.to_string(),
             },
+            Hint {
+                name: "Add subtask",
+                bind: keys.add_subtask.to_string(),
+            },
+            Hint {
+                name: "Indent",
+                bind: keys.indent_subtask.to_string(),
+            },
+            Hint {
+                name: "Outdent",
+                bind: keys.outdent_subtask.to_string(),
+            },
+            Hint {
+                name: "Expand",
+                bind: keys.move_right.to_string(),
+            },
+            Hint {
+                name: "Collapse",
+                bind: keys.move_left.to_string(),
+            },
             Hint {
                 name: "Find",
                 bind: keys.find_mode.to_string(),
             },
+            Hint {
+                name: "Calendar",
+                bind: keys.calendar_view.to_string(),
+            },
+            Hint {
+                name: "Agenda",
+                bind: keys.agenda_view.to_string(),
+            },
+            Hint {
+                name: "Filter tags",
+                bind: keys.filter_tags.to_string(),
+            },
+            Hint {
+                name: "Sort",
+                bind: keys.sort_mode.to_string(),
+            },
+            Hint {
+                name: "Group",
+                bind: keys.group_mode.to_string(),
+            },
+            Hint {
+                name: "Priority up",
+                bind: keys.priority_up.to_string(),
+            },
+            Hint {
+                name: "Priority down",
+                bind: keys.priority_down.to_string(),
+            },
             Hint {
                 name: "Move",
                 bind: keys.move_mode.to_string(),
@@ -72,6 +127,26 @@ impl HintBar {
                 name: "Delete",
                 bind: keys.remove_todo.to_string(),
             },
+            Hint {
+                name: "Lists",
+                bind: keys.switch_list.to_string(),
+            },
+            Hint {
+                name: "Move to list",
+                bind: keys.move_to_list.to_string(),
+            },
+            Hint {
+                name: "Sync",
+                bind: keys.sync.to_string(),
+            },
+            Hint {
+                name: "Undo",
+                bind: keys.undo.to_string(),
+            },
+            Hint {
+                name: "Redo",
+                bind: keys.redo.to_string(),
+            },
             Hint {
                 name: "Desc Up",
                 bind: keys.desc_scroll_up.to_string(),
@@ -98,11 +173,19 @@ impl HintBar {
                 bind: keys.external_editor.to_string(),
             },
             Hint {
-                name: "Mark recurring",
+                name: "Recurrence",
                 bind: keys.mark_recurring.to_string(),
             },
             Hint {
-                name: "Due date",
+                name: "Next field",
+                bind: keys.alt_move_down.to_string(),
+            },
+            Hint {
+                name: "Priority",
+                bind: keys.cycle_priority.to_string(),
+            },
+            Hint {
+                name: "Calendar",
                 bind: keys.open_calendar.to_string(),
             },
             Hint {
@@ -186,6 +269,196 @@ impl HintBar {
                 name: "Prev month",
                 bind: keys.alt_move_left.to_string(),
             },
+            Hint {
+                name: "Next year",
+                bind: keys.next_year.to_string(),
+            },
+            Hint {
+                name: "Prev year",
+                bind: keys.prev_year.to_string(),
+            },
+            Hint {
+                name: "Today",
+                bind: keys.go_to_today.to_string(),
+            },
+        ];
+        Self { hints, theme }
+    }
+
+    pub fn lists_mode(keys: SharedKeyList, theme: SharedTheme) -> Self {
+        let hints = vec![
+            Hint {
+                name: "Back",
+                bind: keys.back.to_string(),
+            },
+            Hint {
+                name: "Up",
+                bind: keys.move_up.to_string(),
+            },
+            Hint {
+                name: "Down",
+                bind: keys.move_down.to_string(),
+            },
+            Hint {
+                name: "Select",
+                bind: keys.submit.to_string(),
+            },
+            Hint {
+                name: "New",
+                bind: keys.add_todo.to_string(),
+            },
+            Hint {
+                name: "Rename",
+                bind: keys.edit_todo.to_string(),
+            },
+            Hint {
+                name: "Delete",
+                bind: keys.remove_todo.to_string(),
+            },
+        ];
+        Self { hints, theme }
+    }
+
+    pub fn subtask_mode(keys: SharedKeyList, theme: SharedTheme) -> Self {
+        let hints = vec![
+            Hint {
+                name: "Back",
+                bind: keys.back.to_string(),
+            },
+            Hint {
+                name: "Save",
+                bind: keys.submit.to_string(),
+            },
+        ];
+        Self { hints, theme }
+    }
+
+    pub fn filter_mode(keys: SharedKeyList, theme: SharedTheme) -> Self {
+        let hints = vec![
+            Hint {
+                name: "Back",
+                bind: keys.back.to_string(),
+            },
+            Hint {
+                name: "Up",
+                bind: keys.move_up.to_string(),
+            },
+            Hint {
+                name: "Down",
+                bind: keys.move_down.to_string(),
+            },
+            Hint {
+                name: "Toggle",
+                bind: keys.toggle_completed.to_string(),
+            },
+            Hint {
+                name: "Clear",
+                bind: keys.remove_todo.to_string(),
+            },
+            Hint {
+                name: "Apply",
+                bind: keys.submit.to_string(),
+            },
+        ];
+        Self { hints, theme }
+    }
+
+    pub fn conflict_mode(keys: SharedKeyList, theme: SharedTheme) -> Self {
+        let hints = vec![
+            Hint {
+                name: "Later",
+                bind: keys.back.to_string(),
+            },
+            Hint {
+                name: "Local",
+                bind: keys.move_left.to_string(),
+            },
+            Hint {
+                name: "Remote",
+                bind: keys.move_right.to_string(),
+            },
+            Hint {
+                name: "Keep",
+                bind: keys.submit.to_string(),
+            },
+        ];
+        Self { hints, theme }
+    }
+
+    pub fn disk_changed_mode(keys: SharedKeyList, theme: SharedTheme) -> Self {
+        let hints = vec![
+            Hint {
+                name: "Up",
+                bind: keys.move_up.to_string(),
+            },
+            Hint {
+                name: "Down",
+                bind: keys.move_down.to_string(),
+            },
+            Hint {
+                name: "Select",
+                bind: keys.submit.to_string(),
+            },
+        ];
+        Self { hints, theme }
+    }
+
+    pub fn calendar_mode(keys: SharedKeyList, theme: SharedTheme) -> Self {
+        let hints = vec![
+            Hint {
+                name: "Back",
+                bind: keys.back.to_string(),
+            },
+            Hint {
+                name: "Open day",
+                bind: keys.submit.to_string(),
+            },
+            Hint {
+                name: "Reschedule",
+                bind: keys.move_mode.to_string(),
+            },
+            Hint {
+                name: "Next month",
+                bind: keys.alt_move_right.to_string(),
+            },
+            Hint {
+                name: "Prev month",
+                bind: keys.alt_move_left.to_string(),
+            },
+            Hint {
+                name: "Next year",
+                bind: keys.next_year.to_string(),
+            },
+            Hint {
+                name: "Prev year",
+                bind: keys.prev_year.to_string(),
+            },
+            Hint {
+                name: "Today",
+                bind: keys.go_to_today.to_string(),
+            },
+        ];
+        Self { hints, theme }
+    }
+
+    pub fn agenda_mode(keys: SharedKeyList, theme: SharedTheme) -> Self {
+        let hints = vec![
+            Hint {
+                name: "Back",
+                bind: keys.back.to_string(),
+            },
+            Hint {
+                name: "Up",
+                bind: keys.move_up.to_string(),
+            },
+            Hint {
+                name: "Down",
+                bind: keys.move_down.to_string(),
+            },
+            Hint {
+                name: "Go to todo",
+                bind: keys.submit.to_string(),
+            },
         ];
         Self { hints, theme }
     }